@@ -0,0 +1,127 @@
+//! Long running work, generating and solving, done in small slices with a pause for rendering and input in between,
+//! so the UI stays responsive and the work can be cancelled.
+
+use dioxus::document::eval;
+use dioxus::prelude::*;
+use std::sync::Arc;
+use sudoku_core::{Budget, CancelToken, ErrorNoSolution, GenerateError, Interrupted, Layout, PuzzleGenerator, SolveError, Sudoku};
+
+use crate::difficultyPicker::{Difficulty, Variant};
+use crate::message::{Message, MessageState};
+
+/// Generator steps done between two chances for the UI to render
+const STEPS_PER_YIELD: u64 = 8;
+/// Solver iterations done between two chances for the UI to render, a few milliseconds of work
+const SOLVE_ITERATIONS_PER_YIELD: u64 = 20_000;
+/// Solver iterations after which solving gives up, several seconds of work
+const MAX_SOLVE_ITERATIONS: u64 = 50_000_000;
+/// Solver iterations spent looking for the entries to blame for a board without solution, a few frames of work.
+/// The message falls back to a general advice if they aren't found in time.
+const DIAGNOSIS_ITERATIONS: u64 = 100_000;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TaskKind {
+    Generating,
+    Solving,
+}
+
+/// Work running in the background, the signal holding it is `None` when nothing runs
+#[derive(Clone)]
+pub struct Task {
+    pub kind: TaskKind,
+    token: CancelToken,
+}
+
+impl Task {
+    /// Stops the work at its next slice, leaving the board as it is
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+}
+
+/// Generates a new puzzle of the variant without blocking the UI, `layout` is `Variant::layout` of the picked shape. <br>
+/// `task` is set until the new puzzle is on the board or the message says why there is none.
+pub fn spawn_new_game(difficulty: Difficulty, variant: Variant, layout: Arc<Layout>, mut board: Signal<Sudoku>, task: Signal<Option<Task>>, mut message: Signal<Message>) {
+    let token = start(task, TaskKind::Generating);
+    spawn(async move {
+        let mut generator = PuzzleGenerator::new(difficulty.target(variant, layout.shape()), rand::rng()).with_layout(layout);
+        let slice = Budget::unlimited().with_iterations(STEPS_PER_YIELD).with_cancel_token(token);
+        loop {
+            match generator.generate_within(&slice) {
+                Ok(sudoku) => {
+                    board.set(sudoku);
+                    break;
+                },
+                Err(GenerateError::Interrupted(Interrupted::Cancelled)) => break,
+                Err(GenerateError::Interrupted(Interrupted::Timeout)) => sleep(0).await,
+                Err(GenerateError::NoGrid) => {
+                    message.write().set_no_grid();
+                    break;
+                }
+            }
+        }
+        finish(task);
+    });
+}
+
+/// Solves the board without blocking the UI, giving up if it takes too long. <br>
+/// `task` is set until the solution is on the board or the reason there is none is in the message.
+pub fn spawn_solve(mut board: Signal<Sudoku>, task: Signal<Option<Task>>, mut message: Signal<Message>, mut diagnostics: Signal<Option<ErrorNoSolution>>) {
+    let solver = board.read().solver();
+    let mut solver = match solver {
+        Ok(solver) => solver,
+        Err(e) => {
+            message.write().set_no_solution(&e);
+            diagnostics.set(Some(e));
+            return;
+        }
+    };
+
+    let token = start(task, TaskKind::Solving);
+    spawn(async move {
+        let slice = Budget::unlimited().with_iterations(SOLVE_ITERATIONS_PER_YIELD).with_cancel_token(token.clone());
+        let diagnosis = Budget::unlimited().with_iterations(DIAGNOSIS_ITERATIONS).with_cancel_token(token);
+        let mut spent = 0;
+        loop {
+            match solver.solve_within(&slice) {
+                Ok(solution) => {
+                    board.write().apply_solution(solution);
+                    message.write().set(MessageState::None);
+                    break;
+                },
+                Err(SolveError::NoSolution(_)) => {
+                    let e = board.read().explain_no_solution_within(&diagnosis);
+                    message.write().set_no_solution(&e);
+                    diagnostics.set(Some(e));
+                    break;
+                },
+                Err(SolveError::Interrupted(Interrupted::Cancelled)) => break,
+                Err(SolveError::Interrupted(Interrupted::Timeout)) => {
+                    spent += SOLVE_ITERATIONS_PER_YIELD;
+                    if spent >= MAX_SOLVE_ITERATIONS {
+                        message.write().set_timeout();
+                        break;
+                    }
+                    sleep(0).await;
+                },
+            }
+        }
+        finish(task);
+    });
+}
+
+fn start(mut task: Signal<Option<Task>>, kind: TaskKind) -> CancelToken {
+    let token = CancelToken::new();
+    task.set(Some(Task { kind, token: token.clone() }));
+    token
+}
+
+fn finish(mut task: Signal<Option<Task>>) {
+    task.set(None);
+}
+
+/// Waits at least `ms` milliseconds, giving the UI a chance to render and handle input even for 0. <br>
+/// A plain wake-up isn't enough on the web, where pending futures run before the browser gets to paint.
+pub async fn sleep(ms: u32) {
+    let _ = eval(&format!("await new Promise(resolve => setTimeout(resolve, {ms}));")).await;
+}
@@ -1,24 +1,49 @@
 use dioxus::prelude::*;
 use dioxus_logger::tracing::info;
 use crate::message::Message;
-use crate::sudoku::Sudoku;
+use sudoku_core::{ErrorNoSolution, SolveStep, Sudoku};
 use crate::tile::Tile;
+use crate::cageOutline::CageOutline;
+use crate::visualizer::{VisualTile, Visualization};
 
 #[component]
-pub fn Board(board: Signal<Sudoku>, focused:Signal<Option<(usize, usize)>>, message: Signal<Message>) -> Element {
+pub fn Board(board: Signal<Sudoku>, focused:Signal<Option<(usize, usize)>>, message: Signal<Message>, hint: Signal<Option<SolveStep>>, notes_mode: Signal<bool>, auto_clear_notes: Signal<bool>, diagnostics: Signal<Option<ErrorNoSolution>>, visualization: Signal<Option<Visualization>>) -> Element {
+    let shape = board.read().shape();
+    let regions = board.read().layout().region_map();
     rsx! {
         table {
         class: "border-collapse border-solid border-3 border-black",
-        for y in 0..9 {
-            tr{ for x in 0..9 {
+        // Read by the tiles to fit the board on the screen whatever its size
+        style: "--cells: {shape.size()}; --note-columns: {shape.box_width()};",
+        for y in 0..shape.size() {
+            tr{ for x in 0..shape.size() {
                 td {
-                    class: format!("p-0 border-solid border {x} {y}",
-                            x=if x%3 == 0 && x != 0 {"border-l-3"} else {""},
-                            y=if y%3 == 0 && y != 0 {"border-t-3"} else {""}),
-                    Tile {
+                    // Thick borders between regions, whether boxes or Jigsaw pieces
+                    class: format!("relative p-0 border-solid border {x} {y}",
+                            x=if x != 0 && regions[y][x - 1] != regions[y][x] {"border-l-3"} else {""},
+                            y=if y != 0 && regions[y - 1][x] != regions[y][x] {"border-t-3"} else {""}),
+                    if visualization.read().is_some() {
+                        VisualTile {
+                            board: board,
+                            visualization: visualization,
+                            x: x,
+                            y: y,
+                        }
+                    } else {
+                        Tile {
+                            board: board,
+                            focused: focused,
+                            message: message,
+                            hint: hint,
+                            notes_mode: notes_mode,
+                            auto_clear_notes: auto_clear_notes,
+                            diagnostics: diagnostics,
+                            x: x,
+                            y: y,
+                        }
+                    }
+                    CageOutline {
                         board: board,
-                        focused: focused,
-                        message: message,
                         x: x,
                         y: y,
                     }}
@@ -0,0 +1,41 @@
+use dioxus::prelude::*;
+use sudoku_core::Sudoku;
+use crate::tile::NOTES_TEXT_SIZE;
+
+/// Dashed outline of the Killer Sudoku cage holding the cell, drawn over the tile on the sides facing other cages,
+/// with the sum of the cage in its top left cell
+#[component]
+pub fn CageOutline(board: Signal<Sudoku>, x: usize, y: usize) -> Element {
+    let board = board.read();
+    let layout = board.layout();
+    let Some(cage) = layout.cage_at(x, y) else {
+        return rsx! {};
+    };
+    let size = layout.size();
+    let other_cage = |nx: Option<usize>, ny: Option<usize>| match (nx, ny) {
+        (Some(nx), Some(ny)) if nx < size && ny < size => layout.cage_at(nx, ny) != Some(cage),
+        _ => true,
+    };
+    // The line keeps a small gap to the cell border where the cage ends, and runs through where it goes on
+    let sides = [
+        if other_cage(Some(x), y.checked_sub(1)) {"top-[3px] border-t"} else {"top-0"},
+        if other_cage(Some(x), Some(y + 1)) {"bottom-[3px] border-b"} else {"bottom-0"},
+        if other_cage(x.checked_sub(1), Some(y)) {"left-[3px] border-l"} else {"left-0"},
+        if other_cage(Some(x + 1), Some(y)) {"right-[3px] border-r"} else {"right-0"},
+    ].join(" ");
+    let cage = &layout.cages()[cage];
+    let is_labelled = cage.cells()[0] == y * size + x;
+    let sum = cage.sum();
+
+    rsx! {
+        div {
+            class: "absolute {sides} border-dashed border-gray-600 pointer-events-none",
+        }
+        if is_labelled {
+            span {
+                class: "absolute top-[1px] left-[2px] px-px {NOTES_TEXT_SIZE} leading-none text-gray-800 bg-white pointer-events-none",
+                "{sum}"
+            }
+        }
+    }
+}
@@ -3,13 +3,21 @@ use dioxus::document::eval;
 use dioxus::prelude::*;
 use dioxus_logger::tracing::info;
 use crate::message::Message;
-use crate::sudoku::Sudoku;
+use sudoku_core::{ErrorNoSolution, PuzzleError, Shape, SolveStep, Sudoku};
 use crate::message::*;
 use crate::popover::Popover;
+use crate::difficultyPicker::{Difficulty, DifficultyPicker, Variant};
+use crate::background::{spawn_new_game, spawn_solve, Task, TaskKind};
+use crate::visualizer::{start_visualization, Visualization};
+use crate::regionMap::RegionMap;
 
 #[component]
-pub fn Controls(board: Signal<Sudoku>, focused:Signal<Option<(usize, usize)>>, message: Signal<Message>) -> Element {
+pub fn Controls(board: Signal<Sudoku>, focused:Signal<Option<(usize, usize)>>, message: Signal<Message>, hint: Signal<Option<SolveStep>>, diagnostics: Signal<Option<ErrorNoSolution>>, entering_puzzle: Signal<bool>, difficulty: Signal<Difficulty>, shape: Signal<Shape>, variant: Signal<Variant>, regions: Signal<Vec<Vec<usize>>>, task: Signal<Option<Task>>, visualization: Signal<Option<Visualization>>) -> Element {
     let mut show = use_signal(|| false);
+    let busy = task.read().is_some();
+    let running = task.read().as_ref().map(|task| task.kind);
+    // The techniques behind hints don't use the sums of Killer cages, so they would rarely find anything
+    let killer = !board.read().layout().cages().is_empty();
 
     use_effect(move || {
         if (message.read().state == MessageState::None) {
@@ -19,6 +27,67 @@ pub fn Controls(board: Signal<Sudoku>, focused:Signal<Option<(usize, usize)>>, m
         }
     });
 
+    use_effect(move || {
+        // Any change to the board makes the shown hint and diagnostics outdated
+        board.read();
+        hint.set(None);
+        diagnostics.set(None);
+        // A solution found for the old board would overwrite the change
+        if let Some(task) = &*task.peek() {
+            if task.kind == TaskKind::Solving {
+                task.cancel();
+            }
+        }
+    });
+
+    let show_hint = move |_| {
+        // The first press shows the hint, the second one places the value
+        let shown = hint.read().clone();
+        if let Some(step) = shown {
+            if let Some(value) = step.placed_value() {
+                board.write().set_cell(step.cell.x, step.cell.y, Some(value));
+            }
+            hint.set(None);
+            if board.read().check() {
+                message.write().set(MessageState::Solved);
+            } else {
+                message.write().set(MessageState::None);
+            }
+            return;
+        }
+
+        let found = board.read().hint();
+        match found {
+            Ok(Some(step)) => {
+                message.write().set_hint(step.to_string());
+                hint.set(Some(step));
+            },
+            Ok(None) => message.write().set(MessageState::Hint),
+            Err(e) => {
+                message.write().set_no_solution(&e);
+                diagnostics.set(Some(e));
+            },
+        }
+    };
+
+    let start = move |_| {
+        // Checked before writing, so the diagnostics aren't cleared right away by the board changing
+        let checked = board.read().validate_puzzle();
+        match checked {
+            Ok(()) => {
+                board.write().lock_givens();
+                entering_puzzle.set(false);
+                message.write().set(MessageState::None);
+            },
+            Err(e) => {
+                message.write().set_invalid_puzzle(&e);
+                if let PuzzleError::NoSolution(e) = e {
+                    diagnostics.set(Some(e));
+                }
+            },
+        }
+    };
+
     rsx! {
         Popover {
             id: "message-popup",
@@ -27,6 +96,8 @@ pub fn Controls(board: Signal<Sudoku>, focused:Signal<Option<(usize, usize)>>, m
             class: format!("{}",
                 if message.read().state == MessageState::Error {
                     "bg-red-50 text-red-500"
+                } else if message.read().state == MessageState::Hint {
+                    "bg-sky-50 text-sky-600"
                 } else {
                     "bg-green-50 text-green-500"
                 }
@@ -38,24 +109,116 @@ pub fn Controls(board: Signal<Sudoku>, focused:Signal<Option<(usize, usize)>>, m
         div {
             class: "flex justify-center flex-wrap m-2 md:m-4 lg:m-6",
 
+            DifficultyPicker {
+                difficulty: difficulty,
+                shape: shape,
+                variant: variant,
+                regions: regions,
+                disabled: busy,
+            }
+
+            RegionMap {
+                board: board,
+                regions: regions,
+                variant: variant,
+                disabled: busy,
+            }
+
             button {
-                class: "transition text-xl bg-emerald-500 hover:bg-emerald-600 active:bg-emerald-700 text-white font-bold py-2 px-3 m-2 border-solid border-emerald-800 border-2 rounded-lg",
+                class: "transition text-xl bg-emerald-500 enabled:hover:bg-emerald-600 enabled:active:bg-emerald-700 disabled:opacity-40 text-white font-bold py-2 px-3 m-2 border-solid border-emerald-800 border-2 rounded-lg",
+                disabled: busy,
                 onclick: move |_| {
-                    board.set(Sudoku::new_puzzle(40));
+                    entering_puzzle.set(false);
+                    message.write().set(MessageState::None);
+                    spawn_new_game(difficulty(), variant(), variant().layout(shape(), &regions.read()), board, task, message);
                 },
-                "New"
+                if running == Some(TaskKind::Generating) {
+                    span { class: "animate-pulse", "Generating..." }
+                } else {
+                    "New"
+                }
             }
 
-            button {
-                class: "transition text-xl bg-sky-500 hover:bg-sky-600 active:bg-sky-700 text-white font-bold py-2 px-4 m-2 border-solid border-sky-800 border-2 rounded-lg",
-                onclick: move |_| {
-                    if let Err(e) = board.write().solve() {
-                        message.write().set(MessageState::Error);
-                        } else {
+            if *entering_puzzle.read() {
+                button {
+                    class: "transition text-xl bg-violet-500 enabled:hover:bg-violet-600 enabled:active:bg-violet-700 disabled:opacity-40 text-white font-bold py-2 px-4 m-2 border-solid border-violet-800 border-2 rounded-lg",
+                    title: "Lock the entered values as the givens and start playing",
+                    disabled: busy,
+                    onclick: start,
+                    "Start"
+                }
+            } else {
+                button {
+                    class: "transition text-xl bg-violet-500 enabled:hover:bg-violet-600 enabled:active:bg-violet-700 disabled:opacity-40 text-white font-bold py-2 px-4 m-2 border-solid border-violet-800 border-2 rounded-lg",
+                    title: "Type in a puzzle, e.g. from a newspaper",
+                    disabled: busy,
+                    onclick: move |_| {
+                        entering_puzzle.set(true);
                         message.write().set(MessageState::None);
+                        board.set(Sudoku::with_layout(variant().layout(shape(), &regions.read())));
+                    },
+                    "Enter puzzle"
+                }
+
+                button {
+                    class: "transition text-xl bg-amber-500 enabled:hover:bg-amber-600 enabled:active:bg-amber-700 disabled:opacity-40 text-white font-bold py-2 px-4 m-2 border-solid border-amber-800 border-2 rounded-lg",
+                    title: if killer { "Hints aren't available for Killer Sudoku, they don't know the cage sums" } else { "Show the next logical move, press again to place it" },
+                    disabled: busy || killer,
+                    onclick: show_hint,
+                    "Hint"
+                }
+
+                button {
+                    class: "transition text-xl bg-sky-500 enabled:hover:bg-sky-600 enabled:active:bg-sky-700 disabled:opacity-40 text-white font-bold py-2 px-4 m-2 border-solid border-sky-800 border-2 rounded-lg",
+                    disabled: busy,
+                    onclick: move |_| spawn_solve(board, task, message, diagnostics),
+                    if running == Some(TaskKind::Solving) {
+                        span { class: "animate-pulse", "Solving..." }
+                    } else {
+                        "Solve"
                     }
+                }
+
+                button {
+                    class: "transition text-xl bg-teal-500 enabled:hover:bg-teal-600 enabled:active:bg-teal-700 disabled:opacity-40 text-white font-bold py-2 px-4 m-2 border-solid border-teal-800 border-2 rounded-lg",
+                    title: "Watch the solver work step by step",
+                    disabled: busy,
+                    onclick: move |_| {
+                        focused.set(None);
+                        message.write().set(MessageState::None);
+                        start_visualization(board, visualization, message, diagnostics);
+                    },
+                    "Visualize"
+                }
+            }
+
+            if let Some(running) = task() {
+                button {
+                    class: "transition text-xl bg-gray-500 hover:bg-gray-600 active:bg-gray-700 text-white font-bold py-2 px-4 m-2 border-solid border-gray-800 border-2 rounded-lg",
+                    title: "Stop and leave the board as it is",
+                    onclick: move |_| running.cancel(),
+                    "Cancel"
+                }
+            }
+
+            button {
+                class: "transition text-xl bg-gray-500 enabled:hover:bg-gray-600 enabled:active:bg-gray-700 disabled:opacity-40 text-white font-bold py-2 px-4 m-2 border-solid border-gray-800 border-2 rounded-lg",
+                disabled: !board.read().history().can_undo(),
+                title: board.read().history().next_undo().map_or(String::new(), |kind| format!("Undo {} (Ctrl+Z)", kind.name())),
+                onclick: move |_| {
+                    board.write().undo();
+                },
+                "Undo"
+            }
+
+            button {
+                class: "transition text-xl bg-gray-500 enabled:hover:bg-gray-600 enabled:active:bg-gray-700 disabled:opacity-40 text-white font-bold py-2 px-4 m-2 border-solid border-gray-800 border-2 rounded-lg",
+                disabled: !board.read().history().can_redo(),
+                title: board.read().history().next_redo().map_or(String::new(), |kind| format!("Redo {} (Ctrl+Y)", kind.name())),
+                onclick: move |_| {
+                    board.write().redo();
                 },
-                "Solve"
+                "Redo"
             }
 
             button {
@@ -0,0 +1,225 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use sudoku_core::{Grade, Layout, PuzzleTarget, Shape};
+
+/// Grades offered in the picker, Extreme puzzles take too long to find
+const GRADES: [Grade; 4] = [Grade::Easy, Grade::Medium, Grade::Hard, Grade::Expert];
+/// No classic sudoku with fewer clues has a single solution
+const MIN_CLASSIC_CLUES: usize = 17;
+const CUSTOM: &str = "custom";
+
+/// Rules of the puzzles started with "New"
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Variant {
+    #[default]
+    Classic,
+    /// Sudoku-X, both main diagonals hold every value once too
+    Diagonal,
+    /// Killer Sudoku, no givens but cages of cells adding up to their sums
+    Killer,
+    /// Jigsaw Sudoku, irregular regions instead of boxes
+    Jigsaw,
+}
+
+impl Variant {
+    const ALL: [Variant; 4] = [Variant::Classic, Variant::Diagonal, Variant::Killer, Variant::Jigsaw];
+
+    fn name(self) -> &'static str {
+        match self {
+            Variant::Classic => "Classic",
+            Variant::Diagonal => "Sudoku-X",
+            Variant::Killer => "Killer",
+            Variant::Jigsaw => "Jigsaw",
+        }
+    }
+
+    /// Constraints of a board of the shape, the generator adds the cages of Killer Sudoku.
+    /// Jigsaw boards get the imported `regions` and their size instead.
+    pub fn layout(self, shape: Shape, regions: &[Vec<usize>]) -> Arc<Layout> {
+        match self {
+            Variant::Classic | Variant::Killer => Layout::shared(shape),
+            Variant::Diagonal => Arc::new(Layout::diagonal(shape)),
+            // The regions were checked when they were imported
+            Variant::Jigsaw => Layout::jigsaw(regions).map_or_else(|_| Layout::shared(shape), Arc::new),
+        }
+    }
+}
+
+/// How hard the puzzles started with "New" are
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Graded(Grade),
+    /// Number of givens the puzzle starts with
+    Clues(usize),
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Difficulty::Graded(Grade::Medium)
+    }
+}
+
+impl Difficulty {
+    /// What the generator aims for on a board of the shape. <br>
+    /// Grades are only rated on classic boards, the ratings are calibrated for them and get slow on big boards,
+    /// elsewhere a grade stands for a share of empty cells. Killer puzzles have no givens, harder ones get bigger cages.
+    pub fn target(self, variant: Variant, shape: Shape) -> PuzzleTarget {
+        match self {
+            _ if variant == Variant::Killer => PuzzleTarget::Cages(self.cage_cells()),
+            Difficulty::Graded(grade) if shape == Shape::CLASSIC && variant == Variant::Classic => PuzzleTarget::Grade(grade),
+            Difficulty::Graded(grade) => PuzzleTarget::EmptyCells(shape.cell_count() * empty_percent(grade) / 100),
+            Difficulty::Clues(clues) => PuzzleTarget::EmptyCells(shape.cell_count() - clamp_clues(clues, shape)),
+        }
+    }
+
+    /// Largest cages of a Killer puzzle, custom puzzles get the ones of Medium
+    fn cage_cells(self) -> usize {
+        match self {
+            Difficulty::Graded(Grade::Easy) => 3,
+            Difficulty::Graded(Grade::Medium) | Difficulty::Clues(_) => 4,
+            Difficulty::Graded(Grade::Hard) => 5,
+            Difficulty::Graded(Grade::Expert | Grade::Extreme) => 6,
+        }
+    }
+}
+
+/// Name of the grade in the picker. Killer puzzles aren't rated, so they go by the size of their cages instead.
+fn grade_label(grade: Grade, variant: Variant) -> String {
+    match variant {
+        Variant::Killer => format!("Cages of {}", Difficulty::Graded(grade).cage_cells()),
+        _ => grade.name().to_string(),
+    }
+}
+
+/// Share of the cells emptied for the grade on boards other than the classic one
+fn empty_percent(grade: Grade) -> usize {
+    match grade {
+        Grade::Easy => 45,
+        Grade::Medium => 55,
+        Grade::Hard => 65,
+        Grade::Expert | Grade::Extreme => 75,
+    }
+}
+
+/// Numbers of clues a custom puzzle may ask for, a single solution needs all values but one among the clues
+fn clue_range(shape: Shape) -> RangeInclusive<usize> {
+    let min = if shape == Shape::CLASSIC { MIN_CLASSIC_CLUES } else { shape.size() - 1 };
+    min..=shape.cell_count() - 1
+}
+
+fn clamp_clues(clues: usize, shape: Shape) -> usize {
+    let range = clue_range(shape);
+    clues.clamp(*range.start(), *range.end())
+}
+
+/// Clues of a new custom puzzle, about 4 in 9 cells
+fn default_clues(shape: Shape) -> usize {
+    shape.cell_count() * 4 / 9
+}
+
+#[component]
+pub fn DifficultyPicker(difficulty: Signal<Difficulty>, shape: Signal<Shape>, variant: Signal<Variant>, regions: Signal<Vec<Vec<usize>>>, disabled: bool) -> Element {
+    let selected = match difficulty() {
+        Difficulty::Graded(grade) => grade.name().to_string(),
+        Difficulty::Clues(_) => CUSTOM.to_string(),
+    };
+    // Jigsaw puzzles have the size of their regions rather than the picked one
+    let active_shape = variant().layout(shape(), &regions.read()).shape();
+    let clues = clue_range(active_shape);
+
+    rsx! {
+        div {
+            class: "flex items-center m-2 text-xl",
+            select {
+                class: "py-2 px-2 mr-2 bg-gray-100 text-gray-700 font-bold border-solid border-gray-500 border-2 rounded-lg disabled:opacity-40",
+                title: "Size of new puzzles, Jigsaw puzzles have the size of their regions",
+                disabled: disabled || variant() == Variant::Jigsaw,
+                value: shape().to_string(),
+                onchange: move |e| {
+                    let Some(picked) = Shape::ALL.into_iter().find(|shape| shape.to_string() == e.value()) else {
+                        return;
+                    };
+                    shape.set(picked);
+                    if let Difficulty::Clues(clues) = difficulty() {
+                        difficulty.set(Difficulty::Clues(clamp_clues(clues, picked)));
+                    }
+                },
+                for option_shape in Shape::ALL {
+                    option {
+                        value: option_shape.to_string(),
+                        selected: shape() == option_shape,
+                        "{option_shape}"
+                    }
+                }
+            }
+            select {
+                class: "py-2 px-2 mr-2 bg-gray-100 text-gray-700 font-bold border-solid border-gray-500 border-2 rounded-lg disabled:opacity-40",
+                title: "Rules of new puzzles",
+                disabled: disabled,
+                value: variant().name(),
+                onchange: move |e| {
+                    if let Some(picked) = Variant::ALL.into_iter().find(|variant| variant.name() == e.value()) {
+                        variant.set(picked);
+                        if picked == Variant::Killer && matches!(difficulty(), Difficulty::Clues(_)) {
+                            difficulty.set(Difficulty::default());
+                        }
+                    }
+                },
+                for option_variant in Variant::ALL {
+                    option {
+                        value: option_variant.name(),
+                        selected: variant() == option_variant,
+                        "{option_variant.name()}"
+                    }
+                }
+            }
+            select {
+                class: "py-2 px-2 bg-gray-100 text-gray-700 font-bold border-solid border-gray-500 border-2 rounded-lg disabled:opacity-40",
+                title: if variant() == Variant::Killer { "Largest cages of new puzzles" } else { "Difficulty of new puzzles" },
+                disabled: disabled,
+                value: selected,
+                onchange: move |e| {
+                    let picked = GRADES.into_iter().find(|grade| grade.name() == e.value());
+                    difficulty.set(match picked {
+                        Some(grade) => Difficulty::Graded(grade),
+                        None => Difficulty::Clues(default_clues(active_shape)),
+                    });
+                },
+                for grade in GRADES {
+                    option {
+                        value: grade.name(),
+                        selected: difficulty() == Difficulty::Graded(grade),
+                        "{grade_label(grade, variant())}"
+                    }
+                }
+                // Killer puzzles have no givens to count
+                if variant() != Variant::Killer {
+                    option {
+                        value: CUSTOM,
+                        selected: matches!(difficulty(), Difficulty::Clues(_)),
+                        "Custom"
+                    }
+                }
+            }
+            if let (Difficulty::Clues(clue_count), false) = (difficulty(), variant() == Variant::Killer) {
+                input {
+                    class: "w-20 ml-2 py-2 px-2 bg-gray-100 text-gray-700 font-bold border-solid border-gray-500 border-2 rounded-lg disabled:opacity-40",
+                    r#type: "number",
+                    title: "Number of clues",
+                    disabled: disabled,
+                    min: *clues.start() as i64,
+                    max: *clues.end() as i64,
+                    value: clue_count as i64,
+                    onchange: move |e| {
+                        if let Ok(count) = e.value().parse::<usize>() {
+                            difficulty.set(Difficulty::Clues(clamp_clues(count, active_shape)));
+                        }
+                    },
+                }
+                span { class: "ml-1", "clues" }
+            }
+        }
+    }
+}
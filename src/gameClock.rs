@@ -0,0 +1,51 @@
+//! Counts the time spent playing the game on the board and shows it above the board. <br>
+//! The time is part of the game, so it is saved with it and goes on from there when the game is resumed.
+
+use std::time::Duration;
+use dioxus::prelude::*;
+use sudoku_core::Sudoku;
+
+use crate::background::{sleep, Task};
+use crate::storage;
+use crate::visualizer::Visualization;
+
+const TICK: Duration = Duration::from_secs(1);
+
+#[component]
+pub fn GameClock(board: Signal<Sudoku>, entering_puzzle: Signal<bool>, task: Signal<Option<Task>>, visualization: Signal<Option<Visualization>>) -> Element {
+    let mut shown = use_signal(|| board.peek().elapsed_time());
+    use_effect(move || shown.set(board.read().elapsed_time()));
+
+    use_future(move || async move {
+        loop {
+            sleep(TICK.as_millis() as u32).await;
+            // Only the time spent solving a puzzle counts, not typing one in, waiting for one or watching the solver
+            let has_puzzle = !board.peek().is_empty() || !board.peek().layout().cages().is_empty();
+            let playing = has_puzzle && !board.peek().check()
+                && !*entering_puzzle.peek() && task.peek().is_none() && visualization.peek().is_none();
+            if !playing {
+                continue;
+            }
+            // Without telling the board's subscribers, to them any change makes the shown hint outdated and
+            // cancels the solver. The game is saved here instead of by the effect watching the board.
+            #[allow(deprecated)]
+            board.write_silent().add_elapsed_time(TICK);
+            storage::save_game(&board.peek());
+            shown.set(board.peek().elapsed_time());
+        }
+    });
+
+    let seconds = shown().as_secs();
+    let time = if seconds < 3600 {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    } else {
+        format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+    };
+    rsx! {
+        div {
+            class: "m-1 text-current/70 tabular-nums",
+            title: "Time spent on this game",
+            {time}
+        }
+    }
+}
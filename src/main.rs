@@ -6,18 +6,27 @@ use dioxus_logger::tracing::{info, Level};
 mod tile;
 use tile::Tile;
 
-mod sudoku;
 mod board;
 mod controls;
 mod message;
 mod popover;
 mod numberInput;
+mod storage;
+mod difficultyPicker;
+mod background;
+mod visualizer;
+mod cageOutline;
+mod regionMap;
+mod gameClock;
 
-use sudoku::Sudoku;
+use sudoku_core::{ErrorNoSolution, SolveStep, Sudoku};
+use crate::background::Task;
 use crate::board::Board;
 use crate::controls::Controls;
+use crate::gameClock::GameClock;
 use crate::message::{Message, MessageState};
 use crate::numberInput::NumberInput;
+use crate::visualizer::{Visualization, VisualizerControls};
 
 const TAILWIND_CSS: Asset = asset!("/assets/tailwind.css");
 const GITHUB_LOGO: Asset = asset!("/assets/github-mark.svg");
@@ -28,9 +37,38 @@ fn main() {
 
 #[component]
 fn App() -> Element {
-    let mut board = use_signal(|| Sudoku::new_puzzle(40));
+    let mut board = use_signal(|| storage::load_game().unwrap_or_else(Sudoku::new_empty));
+    let mut difficulty = use_signal(|| storage::load_difficulty().unwrap_or_default());
+    let mut shape = use_signal(|| storage::load_shape().unwrap_or_default());
+    let mut variant = use_signal(|| storage::load_variant().unwrap_or_default());
+    let mut regions = use_signal(|| storage::load_regions().unwrap_or_else(regionMap::default_regions));
+    let mut task = use_signal(|| None::<Task>);
+    let mut visualization = use_signal(|| None::<Visualization>);
     let mut focused = use_signal(|| None::<(usize, usize)>);
     let mut message = use_signal(|| Message::new());
+    let mut hint = use_signal(|| None::<SolveStep>);
+    let mut notes_mode = use_signal(|| false);
+    let mut auto_clear_notes = use_signal(|| true);
+    let mut diagnostics = use_signal(|| None::<ErrorNoSolution>);
+    // A restored board with values but no givens was still being typed in, unless the cages of Killer Sudoku are its clues
+    let mut entering_puzzle = use_signal(|| {
+        let size = board.peek().size();
+        !board.peek().is_empty() && board.peek().layout().cages().is_empty()
+            && (0..size * size).all(|i| !board.peek().is_read_only(i % size, i / size))
+    });
+
+    use_hook(move || {
+        // Nothing was saved, or only an empty board
+        if board.peek().is_empty() && board.peek().layout().cages().is_empty() {
+            let layout = variant.peek().layout(*shape.peek(), &regions.peek());
+            background::spawn_new_game(*difficulty.peek(), *variant.peek(), layout, board, task, message);
+        }
+    });
+    use_effect(move || storage::save_game(&board.read()));
+    use_effect(move || storage::save_difficulty(&difficulty.read()));
+    use_effect(move || storage::save_shape(&shape.read()));
+    use_effect(move || storage::save_variant(&variant.read()));
+    use_effect(move || storage::save_regions(&regions.read()));
 
     rsx! {
         document::Link { rel: "stylesheet", href: TAILWIND_CSS }
@@ -52,21 +90,49 @@ fn App() -> Element {
                 class: "flex flex-wrap portrait:flex-col landscape:flex-row justify-center items-center h-screen",
                 div {
                 class: "flex justify-center items-center flex-col",
-                    Board {
+                    GameClock {
                         board: board,
-                        focused: focused,
-                        message: message,
+                        entering_puzzle: entering_puzzle,
+                        task: task,
+                        visualization: visualization,
                     }
-                    Controls {
+                    Board {
                         board: board,
                         focused: focused,
                         message: message,
+                        hint: hint,
+                        notes_mode: notes_mode,
+                        auto_clear_notes: auto_clear_notes,
+                        diagnostics: diagnostics,
+                        visualization: visualization,
+                    }
+                    if visualization.read().is_some() {
+                        VisualizerControls {
+                            visualization: visualization,
+                        }
+                    } else {
+                        Controls {
+                            board: board,
+                            focused: focused,
+                            message: message,
+                            hint: hint,
+                            diagnostics: diagnostics,
+                            entering_puzzle: entering_puzzle,
+                            difficulty: difficulty,
+                            shape: shape,
+                            variant: variant,
+                            regions: regions,
+                            task: task,
+                            visualization: visualization,
+                        }
                     }
                 }
                 NumberInput {
                     board: board,
                     focused: focused,
                     message: message,
+                    notes_mode: notes_mode,
+                    auto_clear_notes: auto_clear_notes,
                 }
             }
         }
@@ -1,10 +1,14 @@
-#[derive(PartialEq)]
+use sudoku_core::{ErrorNoSolution, PuzzleError};
+
+#[derive(PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum MessageState {
     Solved,
     Error,
+    Hint,
     None
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct Message{
     pub title: String,
     pub message: String,
@@ -30,6 +34,10 @@ impl Message {
                 self.title = String::from("Error!");
                 self.message = String::from("The sudoku, in the current state, has no solution. Try to remove some of the entered values or press clear to bring back the initial state.");
             }
+            MessageState::Hint => {
+                self.title = String::from("Hint");
+                self.message = String::from("No hint available, none of the known techniques can be applied to the current board.");
+            }
             MessageState::None => {
                 self.title = String::from("");
                 self.message = String::from("")
@@ -37,4 +45,52 @@ impl Message {
         }
         self.state = state;
     }
+
+    /// Sets the error message, explaining what makes the sudoku unsolvable
+    pub fn set_no_solution(&mut self, error: &ErrorNoSolution) {
+        self.set(MessageState::Error);
+        let mut message = String::from("The sudoku, in the current state, has no solution.");
+        if !error.duplicates.is_empty() {
+            message.push_str(" Some values repeat in a row, column or region.");
+        }
+        if !error.dead_cells.is_empty() {
+            message.push_str(" The cells marked in orange are left without any possible value.");
+        }
+        if error.culprits.is_empty() {
+            message.push_str(" Try to remove some of the entered values or press clear to bring back the initial state.");
+        } else {
+            message.push_str(" Removing the values marked in red makes it solvable again.");
+        }
+        self.message = message;
+    }
+
+    /// Sets the error message for a typed-in puzzle that can't be started
+    pub fn set_invalid_puzzle(&mut self, error: &PuzzleError) {
+        match error {
+            PuzzleError::NoSolution(e) => self.set_no_solution(e),
+            PuzzleError::MultipleSolutions => {
+                self.set(MessageState::Error);
+                self.message = String::from("The puzzle has more than one solution. Add more clues so that only one solution is left.");
+            }
+        }
+    }
+
+    /// Sets the error message for a board the solver gave up on
+    pub fn set_timeout(&mut self) {
+        self.set(MessageState::Error);
+        self.title = String::from("Gave up");
+        self.message = String::from("Solving took too long, the sudoku is too open to tell whether it has a solution. Try to enter more values or remove some of them.");
+    }
+
+    /// Sets the error message for Jigsaw regions no puzzle could be generated for
+    pub fn set_no_grid(&mut self) {
+        self.set(MessageState::Error);
+        self.title = String::from("No puzzle");
+        self.message = String::from("No grid could be filled for these regions, they may not fit any solution. Pick other regions or another variant.");
+    }
+
+    pub fn set_hint(&mut self, explanation: String) {
+        self.set(MessageState::Hint);
+        self.message = explanation;
+    }
 }
\ No newline at end of file
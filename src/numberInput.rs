@@ -1,21 +1,27 @@
 use crate::message::Message;
 use crate::message::*;
-use crate::sudoku::Sudoku;
+use sudoku_core::{value_symbol, Sudoku};
+use crate::tile::notes_columns;
 use dioxus::prelude::*;
 use dioxus_logger::tracing::info;
 use std::num::NonZeroU8;
 
 #[component]
-pub fn NumberInput(board: Signal<Sudoku>, focused: Signal<Option<(usize, usize)>>, message: Signal<Message>) -> Element {
+pub fn NumberInput(board: Signal<Sudoku>, focused: Signal<Option<(usize, usize)>>, message: Signal<Message>, notes_mode: Signal<bool>, auto_clear_notes: Signal<bool>) -> Element {
+    let shape = board.read().shape();
     rsx! {
         div {
-            class: "grid grid-cols-3 gap-[5%] mx-10 size-40 md:size-50 max-w justify-center items-center",
-            for i in 1..=9 {
+        class: "flex flex-col items-center",
+        div {
+            // Laid out like a box of the board
+            class: "grid {notes_columns(shape.box_width())} gap-[5%] mx-10 w-40 md:w-50 max-w justify-center items-center",
+            for i in 1..=shape.size() as u8 {
                 button {
                     disabled: focused.read().map_or(false, |(fx, fy)| board.read().is_read_only(fx, fy)),
-                    class: "transition text-3xl md:text-4xl aspect-square bg-gray-500 text-white font-bold border-solid border-gray-800 border-2 rounded-lg \
+                    class: format!("transition {} aspect-square bg-gray-500 text-white font-bold border-solid border-gray-800 border-2 rounded-lg \
                             enabled:hover:bg-gray-600 enabled:active:bg-gray-700 \
                             disabled:opacity-40",
+                            if shape.box_width() > 3 {"text-xl md:text-2xl"} else {"text-3xl md:text-4xl"}),
                     onclick: move |e| {
                         e.stop_propagation();
                         let selected = match focused.read().clone() {
@@ -23,15 +29,49 @@ pub fn NumberInput(board: Signal<Sudoku>, focused: Signal<Option<(usize, usize)>
                             _ => None
                         };
                         if let Some((fx, fy)) = selected {
-                            board.write().set_cell(fx, fy, Some(NonZeroU8::new(i).unwrap()));
+                            let value = NonZeroU8::new(i).unwrap();
+                            if notes_mode() {
+                                board.write().toggle_note(fx, fy, value);
+                                return;
+                            }
+                            if auto_clear_notes() {
+                                board.write().set_cell_and_clear_notes(fx, fy, value);
+                            } else {
+                                board.write().set_cell(fx, fy, Some(value));
+                            }
                             if(board.read().check()){
                                 message.write().set(MessageState::Solved);
                             }
                         }
                     },
-                    "{i}"
+                    "{value_symbol(NonZeroU8::new(i).unwrap())}"
                 }
             }
         }
+        div {
+            class: "flex flex-col items-center gap-2 mt-6",
+            button {
+                class: format!("transition text-xl font-bold py-2 px-4 border-solid border-2 rounded-lg {}",
+                    if notes_mode() {"bg-gray-700 text-white border-gray-900"}
+                    else {"bg-gray-100 text-gray-700 border-gray-500 hover:bg-gray-200"}),
+                onclick: move |e| {
+                    // Keeps the focused cell, so notes can be written into it right away
+                    e.stop_propagation();
+                    notes_mode.set(!notes_mode());
+                },
+                if notes_mode() {"Notes: On"} else {"Notes: Off"}
+            }
+            label {
+                class: "flex items-center gap-2 select-none cursor-pointer",
+                onclick: move |e| e.stop_propagation(),
+                input {
+                    r#type: "checkbox",
+                    checked: auto_clear_notes(),
+                    onchange: move |e| auto_clear_notes.set(e.checked()),
+                }
+                "Clear notes when placing a value"
+            }
+        }
+        }
     }
 }
@@ -0,0 +1,91 @@
+use dioxus::prelude::*;
+use sudoku_core::{parse_region_map, region_map_to_text, Layout, Sudoku};
+use crate::difficultyPicker::Variant;
+use crate::popover::Popover;
+
+/// Regions of new Jigsaw puzzles until the player imports others
+const DEFAULT_REGIONS: &str = "\
+112222333
+112222333
+111112333
+444555666
+444555669
+774585699
+744588669
+777788699
+778888999";
+
+pub fn default_regions() -> Vec<Vec<usize>> {
+    parse_region_map(DEFAULT_REGIONS).unwrap()
+}
+
+/// Reads a region map pasted by the player. Returns why it can't be played otherwise.
+pub fn import_regions(text: &str) -> Result<Vec<Vec<usize>>, String> {
+    let regions = parse_region_map(text).map_err(|e| e.to_string())?;
+    // Whether a grid fits them at all is only found out by generating, which says so if none does
+    Layout::jigsaw(&regions).map_err(|e| e.to_string())?;
+    Ok(regions)
+}
+
+/// Exports the regions of the board as text and imports the ones of new Jigsaw puzzles
+#[component]
+pub fn RegionMap(board: Signal<Sudoku>, regions: Signal<Vec<Vec<usize>>>, variant: Signal<Variant>, disabled: bool) -> Element {
+    let mut show = use_signal(|| false);
+    let mut text = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+
+    rsx! {
+        Popover {
+            id: "region-map",
+            title: "Regions",
+            show: show,
+            class: "bg-gray-50 text-gray-700",
+            div {
+                class: "text-sm",
+                "The region of every cell, 1-9 then A-P. Copy the map of the board or paste another one to play Jigsaw with it."
+            }
+            textarea {
+                class: "w-full my-1 p-1 font-mono text-lg tracking-widest leading-tight border-solid border-gray-500 border-2 rounded-lg",
+                rows: board.read().size(),
+                spellcheck: false,
+                value: text(),
+                oninput: move |e| text.set(e.value()),
+            }
+            if let Some(error) = error() {
+                div {
+                    class: "text-sm text-red-500",
+                    "{error}"
+                }
+            }
+            button {
+                class: "transition bg-violet-500 hover:bg-violet-600 active:bg-violet-700 text-white font-bold py-1 px-3 my-1 border-solid border-violet-800 border-2 rounded-lg",
+                title: "New Jigsaw puzzles get these regions",
+                onclick: move |_| {
+                    match import_regions(&text()) {
+                        Ok(imported) => {
+                            regions.set(imported);
+                            variant.set(Variant::Jigsaw);
+                            error.set(None);
+                            show.set(false);
+                        },
+                        Err(e) => error.set(Some(e)),
+                    }
+                },
+                "Use for Jigsaw"
+            }
+        }
+        button {
+            class: "transition text-xl bg-gray-500 enabled:hover:bg-gray-600 enabled:active:bg-gray-700 disabled:opacity-40 text-white font-bold py-2 px-4 m-2 border-solid border-gray-800 border-2 rounded-lg",
+            title: "Export the regions of the board or import a Jigsaw map",
+            disabled: disabled,
+            onclick: move |e| {
+                // Clicks outside the popover close it
+                e.stop_propagation();
+                text.set(region_map_to_text(&board.read().layout().region_map()));
+                error.set(None);
+                show.set(true);
+            },
+            "Regions"
+        }
+    }
+}
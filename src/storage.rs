@@ -0,0 +1,131 @@
+//! Saves the game in progress and the player's settings, so they survive closing the window or reloading the page. <br>
+//! The desktop build keeps them in files under the user's data directory, the web build in localStorage.
+
+use dioxus_logger::tracing::warn;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sudoku_core::{Layout, Shape, Sudoku};
+
+use crate::difficultyPicker::{Difficulty, Variant};
+
+const APP_DIR: &str = "sudoku-solver";
+const GAME_KEY: &str = "game";
+const DIFFICULTY_KEY: &str = "difficulty";
+const SHAPE_KEY: &str = "shape";
+const VARIANT_KEY: &str = "variant";
+const REGIONS_KEY: &str = "regions";
+
+/// Restores the saved game. Returns `None` if there is none, or if it can't be read.
+pub fn load_game() -> Option<Sudoku> {
+    let sudoku: Sudoku = load(GAME_KEY)?;
+    if !sudoku.is_consistent() {
+        warn!("The saved game doesn't fit its board size or its history is damaged, starting a new one");
+        return None;
+    }
+    Some(sudoku)
+}
+
+/// Overwrites the saved game, failures are only logged
+pub fn save_game(sudoku: &Sudoku) {
+    save(GAME_KEY, sudoku);
+}
+
+/// The difficulty picked for the last new game
+pub fn load_difficulty() -> Option<Difficulty> {
+    load(DIFFICULTY_KEY)
+}
+
+/// Remembers the difficulty for the next session
+pub fn save_difficulty(difficulty: &Difficulty) {
+    save(DIFFICULTY_KEY, difficulty);
+}
+
+/// The board size picked for the last new game
+pub fn load_shape() -> Option<Shape> {
+    load(SHAPE_KEY)
+}
+
+/// Remembers the board size for the next session
+pub fn save_shape(shape: &Shape) {
+    save(SHAPE_KEY, shape);
+}
+
+/// The rules picked for the last new game
+pub fn load_variant() -> Option<Variant> {
+    load(VARIANT_KEY)
+}
+
+/// Remembers the rules for the next session
+pub fn save_variant(variant: &Variant) {
+    save(VARIANT_KEY, variant);
+}
+
+/// The regions last imported for Jigsaw puzzles. Returns `None` if they don't make a Jigsaw board.
+pub fn load_regions() -> Option<Vec<Vec<usize>>> {
+    let regions: Vec<Vec<usize>> = load(REGIONS_KEY)?;
+    Layout::jigsaw(&regions).is_ok().then_some(regions)
+}
+
+/// Remembers the imported regions for the next session
+pub fn save_regions(regions: &Vec<Vec<usize>>) {
+    save(REGIONS_KEY, regions);
+}
+
+fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let json = read(key)?;
+    match serde_json::from_str(&json) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warn!("The saved {key} is corrupt, using the default: {e}");
+            None
+        }
+    }
+}
+
+fn save<T: Serialize + ?Sized>(key: &str, value: &T) {
+    match serde_json::to_string(value) {
+        Ok(json) => write(key, &json),
+        Err(e) => warn!("Couldn't save the {key}: {e}"),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn path(key: &str) -> Option<std::path::PathBuf> {
+    Some(dirs::data_dir()?.join(APP_DIR).join(format!("{key}.json")))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read(key: &str) -> Option<String> {
+    std::fs::read_to_string(path(key)?).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write(key: &str, value: &str) {
+    let Some(path) = path(key) else {
+        warn!("No data directory to save the {key} to");
+        return;
+    };
+    let written = path.parent().map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, value));
+    if let Err(e) = written {
+        warn!("Couldn't save the {key} to {}: {e}", path.display());
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read(key: &str) -> Option<String> {
+    local_storage()?.get_item(&format!("{APP_DIR}/{key}")).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write(key: &str, value: &str) {
+    let saved = local_storage().map(|storage| storage.set_item(&format!("{APP_DIR}/{key}"), value));
+    if !matches!(saved, Some(Ok(()))) {
+        warn!("Couldn't save the {key} to localStorage");
+    }
+}
@@ -1,7 +0,0 @@
-mod sudoku;
-mod sudoku_solver;
-mod cell;
-mod sudoku_errors;
-
-pub mod point;
-pub use sudoku::*;
\ No newline at end of file
@@ -2,77 +2,151 @@ use std::num::NonZeroU8;
 use dioxus::prelude::*;
 use dioxus_logger::tracing::info;
 use crate::message::{Message, MessageState};
-use crate::sudoku::Sudoku;
+use sudoku_core::{parse_value, value_symbol, ErrorNoSolution, Point, SolveStep, Sudoku};
+
+// We want the board to take up around 2/3 of the screen, with `--cells` set to the number of rows by the board:
+// x * cells / 100 = 2/3
+// x ≈ 66.6 / cells
+pub const TILE_SIZE: &str = "size-[calc(81vmin/var(--cells))] sm:size-[calc(66.6vmin/var(--cells))] \
+    text-[length:calc(54vmin/var(--cells))] md:text-[length:calc(40.5vmin/var(--cells))] lg:text-[length:calc(27vmin/var(--cells))]";
+/// Notes share the tile between the columns of their grid, set as `--note-columns` by the board
+pub const NOTES_TEXT_SIZE: &str = "text-[length:calc(64.8vmin/var(--cells)/var(--note-columns))] \
+    md:text-[length:calc(48.6vmin/var(--cells)/var(--note-columns))] lg:text-[length:calc(29.7vmin/var(--cells)/var(--note-columns))]";
+
+/// Grid columns laying out one value per cell of a box, so the notes look like a box of the board
+pub fn notes_columns(box_width: usize) -> &'static str {
+    match box_width {
+        2 => "grid-cols-2",
+        4 => "grid-cols-4",
+        5 => "grid-cols-5",
+        _ => "grid-cols-3",
+    }
+}
 
 #[component]
-pub fn Tile(board: Signal<Sudoku>, focused:Signal<Option<(usize, usize)>>, message: Signal<Message>, x: usize, y: usize) -> Element {
+pub fn Tile(board: Signal<Sudoku>, focused:Signal<Option<(usize, usize)>>, message: Signal<Message>, hint: Signal<Option<SolveStep>>, notes_mode: Signal<bool>, auto_clear_notes: Signal<bool>, diagnostics: Signal<Option<ErrorNoSolution>>, x: usize, y: usize) -> Element {
     let handleInput = move |e : KeyboardEvent| {
+        let modifiers = e.modifiers();
+        if modifiers.ctrl() || modifiers.meta() {
+            // Shortcuts shouldn't be treated as typed values
+            if let Key::Character(c) = e.key() {
+                match c.to_lowercase().as_str() {
+                    "z" if modifiers.shift() => { board.write().redo(); },
+                    "z" => { board.write().undo(); },
+                    "y" => { board.write().redo(); },
+                    _ => {}
+                }
+            }
+            return;
+        }
         if board.read().is_read_only(x, y) {
             return;
         }
         if let Key::Character(c) = e.key() {
-            let num =
-                c.parse::<u8>().ok()
-                .and_then(|n|
-                    if n == 0 {
-                        None
-                    } else {
-                        Some(NonZeroU8::new(n).unwrap())
-                    });
-
-            board.write().set_cell(x, y, num);
+            let size = board.read().size();
+            // Values above 9 are typed as letters, anything not on this board clears the cell
+            let num = c.chars().next()
+                .and_then(parse_value)
+                .filter(|n| usize::from(n.get()) <= size);
+
+            if notes_mode() {
+                if let Some(num) = num {
+                    board.write().toggle_note(x, y, num);
+                }
+                return;
+            }
+
+            match num {
+                Some(num) if auto_clear_notes() => board.write().set_cell_and_clear_notes(x, y, num),
+                _ => board.write().set_cell(x, y, num),
+            }
             if(board.read().check()){
                 message.write().set(MessageState::Solved);
             }
             // needs_update(); // was needed before, keeping commented out coz it's hard to find in docs
-        } else {
+        } else if board.read().get_cell(x, y).is_some() {
             // Any key clears
             // It's here mainly for the backspace key to delete the value
             board.write().set_cell(x, y, None);
+        } else {
+            // With no value to delete the notes go instead
+            board.write().clear_notes(x, y);
         }
     };
 
     let shown_value = use_memo(move || {
         if let Some(v) = board.read().get_cell(x, y) {
-            v.to_string()
+            value_symbol(v).to_string()
         } else {
             String::from("")
         }});
 
     let is_focused = focused.read().map_or(false, |(fx, fy)| x == fx && y == fy);
 
+    let shape = board.read().shape();
     let is_focused_neighbour = match focused() {
         Some((fx, fy)) => {
             // Checks whether the focused cell has direct influence on
             // the value of the current cell
-            x == fx || y == fy || (fx/3==x/3 && fy/3==y/3)
+            board.read().layout().are_peers(Point::new(x, y), Point::new(fx, fy))
         },
         _ => false
     };
 
+    // Sudoku-X diagonals are shaded, they need every value once like the rows
+    let is_on_diagonal = board.read().layout().is_on_diagonal(x, y);
+
     let is_read_only = board.read().is_read_only(x, y);
 
+    let is_conflicting = board.read().is_in_conflict(x, y);
+
     let same_as_focused = match focused() {
         Some((fx, fy)) => board.read().get_cell(x, y) == board.read().get_cell(fx, fy),
         _ => false
     };
 
+    let (is_hint_target, is_hint_reason) = match hint.read().as_ref() {
+        Some(step) => (
+            step.cell.x == x && step.cell.y == y,
+            step.reasons.iter().any(|p| p.x == x && p.y == y)
+        ),
+        None => (false, false)
+    };
+
+    let (is_culprit, is_dead) = match diagnostics.read().as_ref() {
+        Some(error) => (
+            error.culprits.iter().any(|p| p.x == x && p.y == y),
+            error.dead_cells.iter().any(|p| p.x == x && p.y == y)
+        ),
+        None => (false, false)
+    };
+
     rsx! {
         div {
             tabindex: "0",
-            // We want the board to take up around 2/3 of the screen and sudoku grid is 9x9 so:
-            // x * 9 / 100 = 2/3
-            // x = 7,4 ≈ 2/3 * 100/9
-            class: format!("size-[9vmin] sm:size-[7.4vmin] text-[6vmin] md:text-[4.5vmin] lg:text-[3vmin] flex justify-center items-center select-none cursor-pointer {} {}",
-                if is_read_only && !is_focused {"bg-zinc-600 text-white font-bold"}
+            class: format!("{TILE_SIZE} flex justify-center items-center select-none cursor-pointer {} {} {} {}",
+                if is_read_only && !is_focused && is_on_diagonal {"bg-zinc-500 text-white font-bold"}
+                else if is_read_only && !is_focused {"bg-zinc-600 text-white font-bold"}
                 else if is_read_only && is_focused {"bg-zinc-700 text-white font-bold"}
                 else if is_focused {"bg-gray-400 font-semibold"}
                 else if is_focused_neighbour {"bg-gray-200"}
+                else if is_on_diagonal {"bg-sky-100 hover:bg-gray-300 active:bg-gray-500"}
                 else {"hover:bg-gray-300 active:bg-gray-500"},
 
                 if is_focused {""}
                 else if same_as_focused && is_focused_neighbour {"!text-red-400"}
                 else if same_as_focused && !is_focused_neighbour {"!text-green-400"}
+                else {""},
+
+                if is_culprit {"ring-4 ring-inset ring-red-500"}
+                else if is_dead {"ring-4 ring-inset ring-orange-400"}
+                else if is_hint_target {"ring-4 ring-inset ring-sky-500"}
+                else if is_hint_reason {"ring-2 ring-inset ring-sky-300"}
+                else {""},
+
+                // Clashing values are marked no matter which cell is focused
+                if is_conflicting && is_read_only {"!bg-red-800"}
+                else if is_conflicting {"!bg-red-200 !text-red-700"}
                 else {""}),
 
             onkeydown: handleInput,
@@ -83,7 +157,21 @@ pub fn Tile(board: Signal<Sudoku>, focused:Signal<Option<(usize, usize)>>, messa
                     focused.set(Some((x, y)));
                 // }
             },
-            "{shown_value}"
+            if !shown_value().is_empty() || !board.read().has_notes(x, y) {
+                "{shown_value}"
+            } else {
+                div {
+                    class: "grid {notes_columns(shape.box_width())} size-full p-[0.3vmin] {NOTES_TEXT_SIZE} leading-none text-gray-500",
+                    for n in (1..=shape.size() as u8).filter_map(NonZeroU8::new) {
+                        span {
+                            class: "flex justify-center items-center",
+                            if board.read().has_note(x, y, n) {
+                                "{value_symbol(n)}"
+                            }
+                        }
+                    }
+                }
+            }
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,356 @@
+//! Visual solve mode: runs the backtracking solver one iteration at a time and animates its events on the board,
+//! showing the possible values of every empty cell as they get ruled out.
+
+use std::collections::VecDeque;
+use std::num::NonZeroU8;
+use dioxus::prelude::*;
+use sudoku_core::{value_symbol, Budget, CancelToken, Cell, ErrorNoSolution, SolveError, SolverEvent, Sudoku, SudokuSolver};
+
+use crate::background::sleep;
+use crate::message::Message;
+use crate::tile::{notes_columns, NOTES_TEXT_SIZE, TILE_SIZE};
+
+/// Events per second the speed slider can pick from
+const SPEEDS: [u32; 10] = [1, 2, 5, 10, 20, 50, 100, 200, 500, 1000];
+const DEFAULT_SPEED: usize = 3;
+/// Time between two frames at speeds too fast to show every event in its own frame
+const FRAME_MS: u32 = 16;
+/// How often a paused animation checks whether it was resumed
+const PAUSED_POLL_MS: u32 = 100;
+
+/// How the cell of the last shown event is marked
+#[derive(Clone, Copy, PartialEq)]
+pub enum Highlight {
+    /// The solver picked the value while others were possible
+    Guess,
+    /// The value was the only one possible
+    Placed,
+    /// The value was ruled out
+    Removed(u8),
+    /// No value is possible any more
+    Contradiction,
+}
+
+/// The board as shown by the animation, cells are indexed by `y * size + x`
+#[derive(Clone)]
+struct Frame {
+    values: Vec<Option<u8>>,
+    /// Possible values of the empty cells, bit `n - 1` stands for the value `n`
+    candidates: Vec<u32>,
+}
+
+/// The solver being animated and the board as far as the animation got
+pub struct Visualization {
+    solver: SudokuSolver,
+    /// Events the solver reported that aren't shown yet
+    pending: VecDeque<SolverEvent>,
+    size: usize,
+    shown: Frame,
+    /// The board before every guess still open, with the guessed value ruled out, to go back to on a backtrack
+    saved: Vec<Frame>,
+    /// The event shown last
+    current: Option<SolverEvent>,
+    /// Set once the search is over: whether it found a solution
+    solved: Option<bool>,
+    guesses: usize,
+    backtracks: usize,
+    pub paused: bool,
+    /// Index into `SPEEDS`
+    pub speed: usize,
+    token: CancelToken,
+}
+
+impl Visualization {
+    fn new(solver: SudokuSolver, token: CancelToken) -> Self {
+        let shown = Frame {
+            values: solver.cells().iter().map(|cell| match cell {
+                Cell::Collapsed(value) => Some(*value),
+                Cell::Uncollapsed(_) => None,
+            }).collect(),
+            candidates: solver.cells().iter().map(|cell| match cell {
+                Cell::Collapsed(_) => 0,
+                Cell::Uncollapsed(_) => cell.get_possible_values(),
+            }).collect(),
+        };
+        Visualization {
+            size: solver.layout().size(),
+            solver,
+            pending: VecDeque::new(),
+            shown,
+            saved: Vec::new(),
+            current: None,
+            solved: None,
+            guesses: 0,
+            backtracks: 0,
+            paused: true,
+            speed: DEFAULT_SPEED,
+            token,
+        }
+    }
+
+    /// Stops the animation for good
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// Shows the next event, running the solver for another iteration if all of its events are shown
+    pub fn step(&mut self) {
+        while self.pending.is_empty() && self.solved.is_none() {
+            self.search();
+        }
+        if let Some(event) = self.pending.pop_front() {
+            self.apply(event);
+        }
+        if self.is_finished() {
+            self.paused = true;
+        }
+    }
+
+    /// Checks if the search is over and every one of its events was shown
+    pub fn is_finished(&self) -> bool {
+        self.solved.is_some() && self.pending.is_empty()
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn value(&self, x: usize, y: usize) -> Option<u8> {
+        self.shown.values[y * self.size + x]
+    }
+
+    pub fn is_candidate(&self, x: usize, y: usize, value: u8) -> bool {
+        self.shown.candidates[y * self.size + x] & (1 << (value - 1)) != 0
+    }
+
+    pub fn highlight(&self, x: usize, y: usize) -> Option<Highlight> {
+        match self.current? {
+            SolverEvent::Collapse { cell, guess, .. } if cell.x == x && cell.y == y => {
+                Some(if guess { Highlight::Guess } else { Highlight::Placed })
+            },
+            SolverEvent::RemoveCandidate { cell, value } if cell.x == x && cell.y == y => Some(Highlight::Removed(value)),
+            SolverEvent::Contradiction { cell } if cell.x == x && cell.y == y => Some(Highlight::Contradiction),
+            _ => None,
+        }
+    }
+
+    /// Explains the event shown last
+    pub fn status(&self) -> String {
+        if self.is_finished() {
+            return match self.solved {
+                Some(true) => format!("Solved after {} guess(es) and {} backtrack(s).", self.guesses, self.backtracks),
+                _ => String::from("No solution, every guess led to a contradiction."),
+            };
+        }
+        match self.current {
+            None => String::from("Press play or step to start the search."),
+            Some(SolverEvent::Collapse { cell, value, guess: true }) => format!("Guess {} at {cell}.", symbol(value)),
+            Some(SolverEvent::Collapse { cell, value, guess: false }) => format!("Place {} at {cell}, the only possible value.", symbol(value)),
+            Some(SolverEvent::RemoveCandidate { cell, value }) => format!("{} is no longer possible at {cell}.", symbol(value)),
+            Some(SolverEvent::Contradiction { cell }) => format!("No value is possible at {cell}, the last guess was wrong."),
+            Some(SolverEvent::Backtrack { depth, .. }) => format!("Back to the last guess, trying another value. {depth} older guess(es) left."),
+        }
+    }
+
+    /// Time to wait before the next frame and the number of events to show in it
+    fn pace(&self) -> (u32, usize) {
+        if self.paused {
+            return (PAUSED_POLL_MS, 0);
+        }
+        let speed = SPEEDS[self.speed];
+        if speed * FRAME_MS >= 1000 {
+            (FRAME_MS, (speed * FRAME_MS / 1000) as usize)
+        } else {
+            (1000 / speed, 1)
+        }
+    }
+
+    fn search(&mut self) {
+        let pending = &mut self.pending;
+        let result = self.solver.solve_observed(&Budget::unlimited().with_iterations(1), &mut |event: &SolverEvent| pending.push_back(*event));
+        match result {
+            Ok(_) => self.solved = Some(true),
+            Err(SolveError::NoSolution(_)) => self.solved = Some(false),
+            Err(SolveError::Interrupted(_)) => {},
+        }
+    }
+
+    fn apply(&mut self, event: SolverEvent) {
+        match event {
+            SolverEvent::Collapse { cell, value, guess } => {
+                let index = cell.y * self.size + cell.x;
+                if guess {
+                    // The solver saves the same board to come back to
+                    let mut saved = self.shown.clone();
+                    saved.candidates[index] &= !(1 << (value - 1));
+                    self.saved.push(saved);
+                    self.guesses += 1;
+                }
+                self.shown.values[index] = Some(value);
+                self.shown.candidates[index] = 0;
+            },
+            SolverEvent::RemoveCandidate { cell, value } => self.shown.candidates[cell.y * self.size + cell.x] &= !(1 << (value - 1)),
+            SolverEvent::Contradiction { .. } => {},
+            SolverEvent::Backtrack { .. } => {
+                self.backtracks += 1;
+                if let Some(saved) = self.saved.pop() {
+                    self.shown = saved;
+                }
+            },
+        }
+        self.current = Some(event);
+    }
+}
+
+/// Character the value is shown as, letters above 9
+fn symbol(value: u8) -> char {
+    NonZeroU8::new(value).map_or('?', value_symbol)
+}
+
+/// Starts animating the solver on the current board, paused before its first step. <br>
+/// The game itself is left as it is, the animation only shows how the solver gets to the solution.
+pub fn start_visualization(board: Signal<Sudoku>, mut visualization: Signal<Option<Visualization>>, mut message: Signal<Message>, mut diagnostics: Signal<Option<ErrorNoSolution>>) {
+    let solver = board.read().solver();
+    let solver = match solver {
+        Ok(solver) => solver,
+        Err(e) => {
+            message.write().set_no_solution(&e);
+            diagnostics.set(Some(e));
+            return;
+        }
+    };
+
+    let token = CancelToken::new();
+    visualization.set(Some(Visualization::new(solver, token.clone())));
+    spawn(async move {
+        while !token.is_cancelled() {
+            let Some((delay, events)) = visualization.read().as_ref().map(Visualization::pace) else { break };
+            sleep(delay).await;
+            if token.is_cancelled() || events == 0 {
+                continue;
+            }
+            if let Some(visualization) = visualization.write().as_mut() {
+                for _ in 0..events {
+                    visualization.step();
+                }
+            }
+        }
+    });
+}
+
+#[component]
+pub fn VisualTile(board: Signal<Sudoku>, visualization: Signal<Option<Visualization>>, x: usize, y: usize) -> Element {
+    let visualization = visualization.read();
+    let Some(visualization) = visualization.as_ref() else { return rsx! {} };
+    let value = visualization.value(x, y);
+    let highlight = visualization.highlight(x, y);
+    let size = visualization.size() as u8;
+    let columns = notes_columns(board.read().shape().box_width());
+    let is_on_diagonal = board.read().layout().is_on_diagonal(x, y);
+
+    rsx! {
+        div {
+            class: format!("{TILE_SIZE} flex justify-center items-center select-none {} {}",
+                if board.read().is_read_only(x, y) && is_on_diagonal {"bg-zinc-500 text-white font-bold"}
+                else if board.read().is_read_only(x, y) {"bg-zinc-600 text-white font-bold"}
+                else if board.read().get_cell(x, y).is_none() && is_on_diagonal {"bg-sky-100 text-sky-700"}
+                else if board.read().get_cell(x, y).is_none() {"text-sky-700"}
+                else if is_on_diagonal {"bg-sky-100"}
+                else {""},
+
+                match highlight {
+                    Some(Highlight::Guess) => "ring-4 ring-inset ring-violet-500",
+                    Some(Highlight::Placed) => "ring-4 ring-inset ring-sky-500",
+                    Some(Highlight::Removed(_)) => "ring-2 ring-inset ring-amber-400",
+                    Some(Highlight::Contradiction) => "ring-4 ring-inset ring-red-500 !bg-red-200",
+                    None => "",
+                }),
+            if let Some(value) = value {
+                "{symbol(value)}"
+            } else {
+                div {
+                    class: "grid {columns} size-full p-[0.3vmin] {NOTES_TEXT_SIZE} leading-none text-gray-500",
+                    for n in 1..=size {
+                        span {
+                            class: "flex justify-center items-center",
+                            if highlight == Some(Highlight::Removed(n)) {
+                                span { class: "text-red-500 font-bold line-through", "{symbol(n)}" }
+                            } else if visualization.is_candidate(x, y, n) {
+                                "{symbol(n)}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn VisualizerControls(visualization: Signal<Option<Visualization>>) -> Element {
+    let Some((status, paused, finished, speed)) = visualization.read().as_ref()
+        .map(|v| (v.status(), v.paused, v.is_finished(), v.speed)) else { return rsx! {} };
+
+    rsx! {
+        div {
+            class: "flex flex-col items-center m-2 md:m-4 lg:m-6",
+            div {
+                class: "text-lg text-center m-2 max-w-[40rem]",
+                "{status}"
+            }
+            div {
+                class: "flex justify-center items-center flex-wrap",
+                button {
+                    class: "transition text-xl bg-sky-500 enabled:hover:bg-sky-600 enabled:active:bg-sky-700 disabled:opacity-40 text-white font-bold py-2 px-4 m-2 border-solid border-sky-800 border-2 rounded-lg",
+                    disabled: finished,
+                    onclick: move |_| {
+                        if let Some(visualization) = visualization.write().as_mut() {
+                            visualization.paused = !visualization.paused;
+                        }
+                    },
+                    if paused { "Play" } else { "Pause" }
+                }
+
+                button {
+                    class: "transition text-xl bg-amber-500 enabled:hover:bg-amber-600 enabled:active:bg-amber-700 disabled:opacity-40 text-white font-bold py-2 px-4 m-2 border-solid border-amber-800 border-2 rounded-lg",
+                    title: "Show the next solver event",
+                    disabled: finished || !paused,
+                    onclick: move |_| {
+                        if let Some(visualization) = visualization.write().as_mut() {
+                            visualization.step();
+                        }
+                    },
+                    "Step"
+                }
+
+                label {
+                    class: "flex items-center gap-2 m-2 text-xl select-none",
+                    "Speed"
+                    input {
+                        r#type: "range",
+                        min: 0,
+                        max: SPEEDS.len() as i64 - 1,
+                        value: speed as i64,
+                        oninput: move |e| {
+                            if let (Ok(speed), Some(visualization)) = (e.value().parse::<usize>(), visualization.write().as_mut()) {
+                                visualization.speed = speed.min(SPEEDS.len() - 1);
+                            }
+                        },
+                    }
+                    span { class: "w-20", "{SPEEDS[speed]}/s" }
+                }
+
+                button {
+                    class: "transition text-xl bg-gray-500 hover:bg-gray-600 active:bg-gray-700 text-white font-bold py-2 px-4 m-2 border-solid border-gray-800 border-2 rounded-lg",
+                    title: "Back to the game, the board is left as it was",
+                    onclick: move |_| {
+                        if let Some(visualization) = visualization.write().take() {
+                            visualization.cancel();
+                        }
+                    },
+                    "Close"
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use sudoku_core::{parse_region_map, Budget, Grade, Layout, PuzzleGenerator, PuzzleTarget, Shape, Sudoku};
+
+/// Solve, generate and rate sudoku puzzles in bulk
+#[derive(Parser)]
+#[command(name = "sudoku", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the solution of every puzzle
+    Solve {
+        #[command(flatten)]
+        input: Input,
+        #[command(flatten)]
+        output: Output,
+        /// Give up on a puzzle after this many seconds
+        #[arg(short, long, value_name = "SECONDS")]
+        timeout: Option<u64>,
+    },
+    /// Generate puzzles with exactly one solution
+    Generate {
+        /// Number of puzzles to generate
+        #[arg(short = 'n', long, default_value_t = 1)]
+        count: usize,
+        /// Number of rows of the board: 4, 6, 9, 12, 16 or 25
+        #[arg(long, default_value = "9", value_parser = parse_size)]
+        size: Shape,
+        #[command(flatten)]
+        variant: Variant,
+        /// Number of empty cells to aim for [default: half of the cells, 40 on a 9x9 board]
+        #[arg(short, long, value_parser = clap::value_parser!(u64).range(1..))]
+        difficulty: Option<u64>,
+        /// Generate puzzles of this grade instead of aiming for a number of empty cells
+        #[arg(short, long, conflicts_with = "difficulty")]
+        grade: Option<GradeArg>,
+        /// Seed for the random generator, the same seed always gives the same puzzles
+        #[arg(short, long)]
+        seed: Option<u64>,
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Print the difficulty rating of every puzzle
+    Rate {
+        #[command(flatten)]
+        input: Input,
+    },
+    /// Print the number of solutions of every puzzle
+    Count {
+        #[command(flatten)]
+        input: Input,
+        /// Stop counting after this many solutions
+        #[arg(short, long, default_value_t = 1000)]
+        limit: usize,
+    },
+}
+
+#[derive(Args)]
+struct Input {
+    /// Files to read puzzles from, stdin is read if none are given.
+    /// Puzzles can be written on one line or as a grid like the one printed by `--grid`,
+    /// with `.` or `0` for empty cells and `A`-`P` for values above 9
+    files: Vec<PathBuf>,
+    #[command(flatten)]
+    variant: Variant,
+}
+
+#[derive(Args)]
+struct Variant {
+    /// Sudoku-X: both main diagonals have to hold every value once too
+    #[arg(short = 'x', long)]
+    diagonal: bool,
+    /// Jigsaw: irregular regions instead of boxes, read from a map with one character per cell
+    /// naming its region, `1`-`9` then `A`-`P`
+    #[arg(short, long, value_name = "FILE")]
+    regions: Option<PathBuf>,
+}
+
+impl Variant {
+    /// Reads the region map given with `--regions`
+    fn read_regions(&self) -> Result<Option<Vec<Vec<usize>>>, String> {
+        let Some(file) = &self.regions else {
+            return Ok(None);
+        };
+        let text = std::fs::read_to_string(file).map_err(|e| format!("{}: {e}", file.display()))?;
+        parse_region_map(&text).map(Some).map_err(|e| format!("{}: {e}", file.display()))
+    }
+
+    /// The rules picked on the command line for a board of the shape, with the regions read by `read_regions`
+    fn layout(&self, shape: Shape, regions: Option<&[Vec<usize>]>) -> Result<Arc<Layout>, String> {
+        let layout = if self.diagonal { Layout::diagonal(shape) } else { Layout::new(shape) };
+        match regions {
+            Some(regions) => layout.with_regions(regions).map(Arc::new).map_err(|e| format!("regions: {e}")),
+            None if self.diagonal => Ok(Arc::new(layout)),
+            None => Ok(Layout::shared(shape)),
+        }
+    }
+}
+
+#[derive(Args)]
+struct Output {
+    /// Print puzzles as a grid instead of one line per puzzle
+    #[arg(long)]
+    grid: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum GradeArg {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+    Extreme,
+}
+
+impl From<GradeArg> for Grade {
+    fn from(grade: GradeArg) -> Self {
+        match grade {
+            GradeArg::Easy => Grade::Easy,
+            GradeArg::Medium => Grade::Medium,
+            GradeArg::Hard => Grade::Hard,
+            GradeArg::Expert => Grade::Expert,
+            GradeArg::Extreme => Grade::Extreme,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Solve { input, output, timeout } => solve(&input, &output, timeout),
+        Command::Generate { count, size, variant, difficulty, grade, seed, output } => {
+            variant.read_regions()
+                .and_then(|regions| variant.layout(size, regions.as_deref()))
+                .and_then(|layout| generate(count, layout, difficulty, grade, seed, &output))
+        }
+        Command::Rate { input } => rate(&input),
+        Command::Count { input, limit } => count(&input, limit),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("sudoku: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Returns false if any of the puzzles has no solution or took too long
+fn solve(input: &Input, output: &Output, timeout: Option<u64>) -> Result<bool, String> {
+    let budget = match timeout {
+        Some(seconds) => Budget::unlimited().with_time(Duration::from_secs(seconds)),
+        None => Budget::unlimited(),
+    };
+    let mut all_solved = true;
+    for (i, mut sudoku) in read_puzzles(input)?.into_iter().enumerate() {
+        match sudoku.solve_within(&budget) {
+            Ok(()) => print_sudoku(&sudoku, output),
+            Err(error) => {
+                eprintln!("puzzle {}: {error}", i + 1);
+                all_solved = false;
+            }
+        }
+    }
+    Ok(all_solved)
+}
+
+fn parse_size(size: &str) -> Result<Shape, String> {
+    let size: usize = size.parse().map_err(|e| format!("{e}"))?;
+    Shape::for_size(size).ok_or_else(|| format!("there is no board with {size} rows"))
+}
+
+fn generate(count: usize, layout: Arc<Layout>, difficulty: Option<u64>, grade: Option<GradeArg>, seed: Option<u64>, output: &Output) -> Result<bool, String> {
+    let shape = layout.shape();
+    let target = match (grade, difficulty) {
+        (Some(grade), _) => PuzzleTarget::Grade(grade.into()),
+        (None, Some(difficulty)) if difficulty > shape.cell_count() as u64 => {
+            return Err(format!("a {shape} board has only {} cells", shape.cell_count()));
+        }
+        (None, Some(difficulty)) => PuzzleTarget::EmptyCells(difficulty as usize),
+        (None, None) => PuzzleTarget::EmptyCells(shape.cell_count() / 2),
+    };
+    let rng: Box<dyn RngCore> = match seed {
+        Some(seed) => Box::new(StdRng::seed_from_u64(seed)),
+        None => Box::new(rand::rng()),
+    };
+    let mut generator = PuzzleGenerator::new(target, rng).with_layout(layout);
+    for _ in 0..count {
+        let sudoku = generator.generate().map_err(|e| e.to_string())?;
+        print_sudoku(&sudoku, output);
+    }
+    Ok(true)
+}
+
+/// Returns false if any of the puzzles has no solution
+fn rate(input: &Input) -> Result<bool, String> {
+    let mut all_rated = true;
+    for (i, sudoku) in read_puzzles(input)?.into_iter().enumerate() {
+        match sudoku.rate() {
+            Ok(rating) => println!("{rating}"),
+            Err(error) => {
+                eprintln!("puzzle {}: {error}", i + 1);
+                all_rated = false;
+            }
+        }
+    }
+    Ok(all_rated)
+}
+
+fn count(input: &Input, limit: usize) -> Result<bool, String> {
+    for sudoku in read_puzzles(input)? {
+        let solutions = sudoku.count_solutions(limit);
+        if solutions == limit {
+            println!("{solutions}+");
+        } else {
+            println!("{solutions}");
+        }
+    }
+    Ok(true)
+}
+
+fn read_puzzles(input: &Input) -> Result<Vec<Sudoku>, String> {
+    let mut puzzles = Vec::new();
+    if input.files.is_empty() {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text).map_err(|e| format!("stdin: {e}"))?;
+        puzzles = Sudoku::parse_many(&text).map_err(|e| format!("stdin: {e}"))?;
+    }
+    for file in &input.files {
+        let text = std::fs::read_to_string(file).map_err(|e| format!("{}: {e}", file.display()))?;
+        puzzles.extend(Sudoku::parse_many(&text).map_err(|e| format!("{}: {e}", file.display()))?);
+    }
+
+    let regions = input.variant.read_regions()?;
+    if !input.variant.diagonal && regions.is_none() {
+        return Ok(puzzles);
+    }
+    // The text formats only hold the values, the rules come from the command line
+    puzzles.into_iter().map(|sudoku| {
+        let size = sudoku.size();
+        let givens = (0..size).map(|y| (0..size).map(|x| sudoku.get_cell(x, y)).collect()).collect();
+        Ok(Sudoku::from_givens_in(input.variant.layout(sudoku.shape(), regions.as_deref())?, givens))
+    }).collect()
+}
+
+fn print_sudoku(sudoku: &Sudoku, output: &Output) {
+    if output.grid {
+        println!("{sudoku}");
+    } else {
+        println!("{}", sudoku.to_line());
+    }
+}
@@ -0,0 +1,45 @@
+use std::num::NonZeroU8;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use sudoku_core::{Sudoku, SudokuSolver};
+
+/// Fixed corpus, one puzzle per line, `0` or `.` for empty cells
+const PUZZLES: &str = include_str!("puzzles.txt");
+
+fn parse_puzzle(line: &str) -> Vec<Vec<Option<NonZeroU8>>> {
+    let mut board = vec![vec![None; 9]; 9];
+    for (i, c) in line.chars().enumerate() {
+        board[i / 9][i % 9] = c.to_digit(10).and_then(|d| NonZeroU8::new(d as u8));
+    }
+    board
+}
+
+fn corpus() -> Vec<Vec<Vec<Option<NonZeroU8>>>> {
+    PUZZLES.lines().filter(|l| !l.trim().is_empty()).map(parse_puzzle).collect()
+}
+
+fn bench_solve(c: &mut Criterion) {
+    let puzzles = corpus();
+    c.bench_function("solve corpus", |b| b.iter(|| {
+        for puzzle in &puzzles {
+            black_box(SudokuSolver::new(puzzle).unwrap().solve().unwrap());
+        }
+    }));
+}
+
+fn bench_uniqueness(c: &mut Criterion) {
+    let puzzles = corpus();
+    c.bench_function("count solutions corpus", |b| b.iter(|| {
+        for puzzle in &puzzles {
+            black_box(SudokuSolver::new(puzzle).unwrap().count_solutions(2));
+        }
+    }));
+}
+
+fn bench_generate(c: &mut Criterion) {
+    c.bench_function("generate puzzle", |b| b.iter(|| black_box(Sudoku::new_puzzle(55))));
+}
+
+criterion_group!(benches, bench_solve, bench_uniqueness, bench_generate);
+criterion_main!(benches);
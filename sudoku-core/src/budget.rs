@@ -0,0 +1,109 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::sudoku_errors::Interrupted;
+
+/// Iterations between two looks at the clock, reading it on every iteration would slow the search down
+const ITERATIONS_PER_CLOCK_CHECK: u64 = 256;
+
+/// Lets another thread or task stop a running search. <br>
+/// Clones share the same flag, so keep one and hand a clone to the `Budget`.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Creates a token that isn't cancelled yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks every search using this token to stop as soon as possible
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Checks if `cancel` was called on this token or one of its clones
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Limits on how long a search may run before it gives up with an `Interrupted` error. <br>
+/// The default budget is unlimited.
+/// ```
+/// use std::time::Duration;
+/// use sudoku_core::{Budget, CancelToken};
+///
+/// let token = CancelToken::new();
+/// let budget = Budget::unlimited()
+///     .with_time(Duration::from_secs(5))
+///     .with_cancel_token(token.clone());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Budget {
+    iterations: Option<u64>,
+    time: Option<Duration>,
+    cancel_token: Option<CancelToken>,
+}
+
+impl Budget {
+    /// A budget that never runs out
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Stops the search after this many iterations, every guess or backtrack of the solver counts as one
+    pub fn with_iterations(mut self, iterations: u64) -> Self {
+        self.iterations = Some(iterations);
+        self
+    }
+
+    /// Stops the search once it has run this long. <br>
+    /// Not available on `wasm32-unknown-unknown`, which has no clock, use `with_iterations` there.
+    pub fn with_time(mut self, time: Duration) -> Self {
+        self.time = Some(time);
+        self
+    }
+
+    /// Stops the search once the token is cancelled
+    pub fn with_cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel_token = Some(token);
+        self
+    }
+
+    /// Starts spending the budget, every search gets a fresh meter
+    pub(crate) fn start(&self) -> Meter<'_> {
+        Meter {
+            budget: self,
+            iterations: 0,
+            deadline: self.time.map(|time| Instant::now() + time),
+        }
+    }
+}
+
+/// The part of a `Budget` spent so far by one search
+pub(crate) struct Meter<'a> {
+    budget: &'a Budget,
+    iterations: u64,
+    deadline: Option<Instant>,
+}
+
+impl Meter<'_> {
+    /// Counts one iteration, fails once the budget is used up or the search was cancelled
+    pub(crate) fn tick(&mut self) -> Result<(), Interrupted> {
+        if self.budget.cancel_token.as_ref().is_some_and(CancelToken::is_cancelled) {
+            return Err(Interrupted::Cancelled);
+        }
+        if self.budget.iterations.is_some_and(|max| self.iterations >= max) {
+            return Err(Interrupted::Timeout);
+        }
+        self.iterations += 1;
+        if let Some(deadline) = self.deadline {
+            if self.iterations.is_multiple_of(ITERATIONS_PER_CLOCK_CHECK) && Instant::now() >= deadline {
+                return Err(Interrupted::Timeout);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+/// Bit `n - 1` of a candidate mask is set when the value `n` is possible
+pub fn value_to_mask(value: u8) -> u32 {
+    1 << (value - 1)
+}
+
+/// Mask with every value of a board with `size` values set
+pub fn all_values(size: usize) -> u32 {
+    u32::MAX >> (32 - size)
+}
+
+/// A cell that can still take any of several values
+#[derive(Debug, Clone, Copy)]
+pub struct UncollapsedCell {
+    possible_values: u32
+}
+
+impl UncollapsedCell {
+    fn new(size: usize) -> Self {
+        UncollapsedCell {
+            possible_values: all_values(size)
+        }
+    }
+
+    fn get_entropy(&self) -> u8 {
+        self.possible_values.count_ones() as u8
+    }
+
+    fn remove(&mut self, value: u8) -> Result<(), ()> {
+        self.restrict(!value_to_mask(value))
+    }
+
+    fn restrict(&mut self, mask: u32) -> Result<(), ()> {
+        self.possible_values &= mask;
+        if self.possible_values == 0 {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A cell of the solver's board
+#[derive(Debug, Clone, Copy)]
+pub enum Cell {
+    /// The cell holds a value
+    Collapsed(u8),
+    /// The cell is still empty
+    Uncollapsed(UncollapsedCell)
+}
+
+impl Cell {
+    /// Creates an empty cell of a board with `size` values, where every value is possible
+    pub fn new_empty(size: usize) -> Self {
+        Cell::Uncollapsed(UncollapsedCell::new(size))
+    }
+
+    /// Creates a cell holding `value`
+    pub fn new_filled(value: u8) -> Self {
+        Cell::Collapsed(value)
+    }
+
+    /// Number of values the cell can still take. Panics on a collapsed cell.
+    pub fn get_entropy(&self) -> u8{
+        match self {
+            Cell::Uncollapsed(c) => c.get_entropy(),
+            Cell::Collapsed(_) => panic!("get_entropy called on a collapsed cell"),
+        }
+    }
+
+    /// Possible values as a mask, where bit `n - 1` stands for the value `n`
+    pub fn get_possible_values(&self) -> u32 {
+        match self {
+            Cell::Uncollapsed(c) => c.possible_values,
+            Cell::Collapsed(v) => value_to_mask(*v),
+        }
+    }
+
+    /// Collapses the cell to the given value. <br>
+    /// If the cell had more than one possible value, the function returns
+    /// a copy of the uncollapsed cell with that value removed. <br>
+    /// Otherwise, it returns the state of the cell before collapsing.
+    pub(crate) fn collapse(&mut self, value: u8) -> Cell{
+        let mut cell = *self;
+        *self = match self {
+            Cell::Uncollapsed(_) => Cell::Collapsed(value),
+            Cell::Collapsed(_) => panic!("collapse called on a collapsed cell"),
+        };
+        if cell.get_entropy() > 1 {
+            cell.remove(value).unwrap();
+        }
+        cell
+    }
+
+    /// Removes `value` from the possible values. <br>
+    /// Fails if the cell is left without a possible value, or if it already holds `value`.
+    pub(crate) fn remove(&mut self, value: u8) -> Result<(), ()> {
+        match self {
+            Cell::Uncollapsed(c) => c.remove(value),
+            Cell::Collapsed(v) => if *v != value { Ok(()) } else { Err(()) }
+        }
+    }
+
+    /// Keeps only the possible values set in `mask`. <br>
+    /// Fails if the cell is left without a possible value, or if its value isn't in `mask`.
+    pub(crate) fn restrict(&mut self, mask: u32) -> Result<(), ()> {
+        match self {
+            Cell::Uncollapsed(c) => c.restrict(mask),
+            Cell::Collapsed(v) => if mask & value_to_mask(*v) != 0 { Ok(()) } else { Err(()) }
+        }
+    }
+
+}
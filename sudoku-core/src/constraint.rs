@@ -0,0 +1,270 @@
+//! The rules a board has to follow. Both the solver and `Sudoku::check` go through the constraints of the `Layout`,
+//! so a variant of the game is a new `Constraint` added with `Layout::with_constraint`.
+//! The cages of Killer Sudoku are constraints too, see `Cage`.
+
+use std::fmt::Debug;
+use std::num::NonZeroU8;
+
+use super::cell::value_to_mask;
+
+/// A rule over some of the cells of a board
+pub trait Constraint: Debug + Send + Sync {
+    /// Cells the rule covers, indexed by `y * size + x`
+    fn cells(&self) -> &[usize];
+
+    /// Whether no two of the cells may hold the same value. <br>
+    /// The cells of such a rule are peers of each other, and if there are as many of them as the board has values
+    /// they form a unit, which must hold every value once.
+    fn values_differ(&self) -> bool;
+
+    /// Rules out the values the constraint forbids, given the possible values of its cells in the order of `cells`.
+    /// Bit `n - 1` stands for the value `n`, a filled cell has only its own value set. <br>
+    /// If the rule can't be satisfied any more, at least one of the masks has to be left empty.
+    fn prune(&self, candidates: &mut [u32]);
+
+    /// Whether `prune` can rule out more than removing placed values from the peers does. <br>
+    /// The solver removes those on its own, so it only calls `prune` on the constraints that can.
+    fn prunes_beyond_peers(&self) -> bool {
+        true
+    }
+
+    /// Checks the values of its cells in the order of `cells`, `None` for empty cells. <br>
+    /// Empty cells are allowed, a full board that satisfies every constraint is solved.
+    fn check(&self, values: &[Option<NonZeroU8>]) -> bool;
+}
+
+/// The cells have to hold different values, e.g. a row, column or box
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllDifferent {
+    cells: Vec<usize>,
+}
+
+impl AllDifferent {
+    /// Creates the rule over the cells, indexed by `y * size + x`
+    pub fn new(cells: Vec<usize>) -> Self {
+        AllDifferent { cells }
+    }
+}
+
+impl Constraint for AllDifferent {
+    fn cells(&self) -> &[usize] {
+        &self.cells
+    }
+
+    fn values_differ(&self) -> bool {
+        true
+    }
+
+    /// Removes the value of every cell with a single possible value from the other cells
+    fn prune(&self, candidates: &mut [u32]) {
+        for index in 0..candidates.len() {
+            let single = candidates[index];
+            if single.count_ones() != 1 {
+                continue;
+            }
+            for (other, mask) in candidates.iter_mut().enumerate() {
+                if other != index {
+                    *mask &= !single;
+                }
+            }
+        }
+    }
+
+    fn prunes_beyond_peers(&self) -> bool {
+        false
+    }
+
+    fn check(&self, values: &[Option<NonZeroU8>]) -> bool {
+        let mut seen = 0u32;
+        for value in values.iter().flatten() {
+            let mask = value_to_mask(value.get());
+            if seen & mask != 0 {
+                return false;
+            }
+            seen |= mask;
+        }
+        true
+    }
+}
+
+/// A cage of Killer Sudoku: its cells hold different values that add up to `sum`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cage {
+    cells: Vec<usize>,
+    sum: u32,
+}
+
+impl Cage {
+    /// Creates the cage over the cells, indexed by `y * size + x`, sorted so the top left cell comes first
+    pub fn new(mut cells: Vec<usize>, sum: u32) -> Self {
+        cells.sort_unstable();
+        Cage { cells, sum }
+    }
+
+    /// Cells of the cage in reading order
+    pub fn cells(&self) -> &[usize] {
+        &self.cells
+    }
+
+    /// What the values of the cage add up to
+    pub fn sum(&self) -> u32 {
+        self.sum
+    }
+}
+
+/// Calls `f` with every set of `count` values from `available` adding up to `sum`, values from `from` upwards
+fn for_each_combination(available: u32, from: u32, count: u32, sum: u32, chosen: u32, f: &mut impl FnMut(u32)) {
+    if count == 0 {
+        if sum == 0 {
+            f(chosen);
+        }
+        return;
+    }
+    let highest = 32 - available.leading_zeros();
+    // The smallest and largest sums `count` different values from `from` up to `highest` can make
+    let triangle = count * (count - 1) / 2;
+    if from > highest || sum < count * from + triangle || sum + triangle > count * highest {
+        return;
+    }
+    for value in from..=highest {
+        let mask = value_to_mask(value as u8);
+        if available & mask != 0 && value <= sum {
+            for_each_combination(available, value + 1, count - 1, sum - value, chosen | mask, f);
+        }
+    }
+}
+
+/// Checks if every cell can get its own value out of `values`, given the possible values of the cells
+fn has_matching(candidates: &[u32], values: u32) -> bool {
+    // Cell holding each value, found by augmenting paths
+    let mut holder = [usize::MAX; 32];
+    fn assign(cell: usize, candidates: &[u32], values: u32, holder: &mut [usize; 32], visited: &mut u32) -> bool {
+        let mut options = candidates[cell] & values;
+        while options != 0 {
+            let value = options.trailing_zeros() as usize;
+            options &= options - 1;
+            if *visited & (1 << value) != 0 {
+                continue;
+            }
+            *visited |= 1 << value;
+            if holder[value] == usize::MAX || assign(holder[value], candidates, values, holder, visited) {
+                holder[value] = cell;
+                return true;
+            }
+        }
+        false
+    }
+    (0..candidates.len()).all(|cell| assign(cell, candidates, values, &mut holder, &mut 0))
+}
+
+impl Constraint for Cage {
+    fn cells(&self) -> &[usize] {
+        &self.cells
+    }
+
+    fn values_differ(&self) -> bool {
+        true
+    }
+
+    /// Keeps only the values a cell can take in a combination adding up to the sum,
+    /// with the other cells taking the rest of the combination
+    fn prune(&self, candidates: &mut [u32]) {
+        let available = candidates.iter().fold(0, |all, &mask| all | mask);
+        let mut supported = vec![0; candidates.len()];
+        let mut forced = candidates.to_vec();
+        for_each_combination(available, 1, candidates.len() as u32, self.sum, 0, &mut |combination| {
+            if !has_matching(candidates, combination) {
+                return;
+            }
+            for cell in 0..candidates.len() {
+                let mut untested = candidates[cell] & combination & !supported[cell];
+                while untested != 0 {
+                    let value = untested & untested.wrapping_neg();
+                    untested &= untested - 1;
+                    forced[cell] = value;
+                    if has_matching(&forced, combination) {
+                        supported[cell] |= value;
+                    }
+                }
+                forced[cell] = candidates[cell];
+            }
+        });
+        for (mask, supported) in candidates.iter_mut().zip(supported) {
+            *mask &= supported;
+        }
+    }
+
+    fn check(&self, values: &[Option<NonZeroU8>]) -> bool {
+        let mut seen = 0u32;
+        let mut total = 0;
+        for value in values.iter().flatten() {
+            let mask = value_to_mask(value.get());
+            if seen & mask != 0 {
+                return false;
+            }
+            seen |= mask;
+            total += u32::from(value.get());
+        }
+        // Every empty cell adds at least 1
+        let empty = values.iter().filter(|value| value.is_none()).count() as u32;
+        if empty == 0 { total == self.sum } else { total + empty <= self.sum }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Masks of the values, e.g. `mask(&[1, 3])` for 1 or 3
+    fn mask(values: &[u8]) -> u32 {
+        values.iter().fold(0, |mask, &value| mask | value_to_mask(value))
+    }
+
+    #[test]
+    fn cage_keeps_the_values_of_fitting_combinations() {
+        // 3 in two cells can only be 1 + 2
+        let mut candidates = vec![mask(&[1, 2, 3, 4]); 2];
+        Cage::new(vec![0, 1], 3).prune(&mut candidates);
+        assert_eq!(candidates, [mask(&[1, 2]); 2]);
+
+        // 24 in three cells of a 9x9 board can only be 7 + 8 + 9
+        let mut candidates = vec![mask(&[1, 2, 3, 4, 5, 6, 7, 8, 9]); 3];
+        Cage::new(vec![0, 1, 2], 24).prune(&mut candidates);
+        assert_eq!(candidates, [mask(&[7, 8, 9]); 3]);
+    }
+
+    #[test]
+    fn cage_takes_placed_values_into_account() {
+        // With a 1 placed, 7 leaves 2 + 4 for the other two cells, 6 + 0 and 3 + 3 don't fit
+        let mut candidates = vec![mask(&[1]), mask(&[1, 2, 3, 4]), mask(&[1, 2, 3, 4])];
+        Cage::new(vec![0, 1, 2], 7).prune(&mut candidates);
+        assert_eq!(candidates, [mask(&[1]), mask(&[2, 4]), mask(&[2, 4])]);
+    }
+
+    #[test]
+    fn cage_removes_values_another_cell_needs() {
+        // 6 in three cells is 1 + 2 + 3, the third cell must be the 3, so the others lose it
+        let mut candidates = vec![mask(&[1, 2, 3]), mask(&[1, 2, 3]), mask(&[3, 4])];
+        Cage::new(vec![0, 1, 2], 6).prune(&mut candidates);
+        assert_eq!(candidates, [mask(&[1, 2]), mask(&[1, 2]), mask(&[3])]);
+    }
+
+    #[test]
+    fn cage_without_fitting_combination_empties_the_cells() {
+        let mut candidates = vec![mask(&[1, 2]); 2];
+        Cage::new(vec![0, 1], 10).prune(&mut candidates);
+        assert_eq!(candidates, [0, 0]);
+    }
+
+    #[test]
+    fn cage_check() {
+        let cage = Cage::new(vec![0, 1, 2], 10);
+        let values = |values: [u8; 3]| values.map(NonZeroU8::new);
+        assert!(cage.check(&values([1, 4, 5])));
+        assert!(!cage.check(&values([2, 4, 5])));
+        assert!(!cage.check(&values([5, 5, 0])));
+        assert!(cage.check(&values([2, 0, 0])));
+        assert!(!cage.check(&values([9, 0, 0])));
+    }
+}
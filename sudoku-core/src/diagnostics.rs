@@ -0,0 +1,127 @@
+//! Works out why a board has no solution
+
+use std::num::NonZeroU8;
+use std::sync::Arc;
+
+use super::budget::Meter;
+use super::cell::value_to_mask;
+use super::layout::Layout;
+use super::point::Point;
+use super::sudoku::SudokuBoard;
+use super::sudoku_errors::{ErrorNoSolution, Interrupted};
+use super::sudoku_solver::SudokuSolver;
+
+fn cell_to_point(layout: &Layout, cell: usize) -> Point<usize> {
+    Point::new(layout.column_of(cell), layout.row_of(cell))
+}
+
+fn value_at(layout: &Layout, board: &SudokuBoard, cell: usize) -> Option<NonZeroU8> {
+    board[layout.row_of(cell)][layout.column_of(cell)]
+}
+
+/// The search for culprits spends the meter, they are left out if it runs out
+pub fn diagnose(layout: &Arc<Layout>, board: &SudokuBoard, read_only: &[Vec<bool>], meter: &mut Meter) -> ErrorNoSolution {
+    ErrorNoSolution {
+        duplicates: find_duplicates(layout, board),
+        dead_cells: find_dead_cells(layout, board),
+        culprits: find_culprits(layout, board, read_only, meter).unwrap_or_default()
+    }
+}
+
+/// Every pair of filled cells that may not hold the same value, but do
+pub fn find_duplicates(layout: &Layout, board: &SudokuBoard) -> Vec<(Point<usize>, Point<usize>)> {
+    let mut duplicates = Vec::new();
+    for cell in 0..layout.cell_count() {
+        let Some(value) = value_at(layout, board, cell) else { continue };
+        for &peer in layout.peers(cell).iter().filter(|&&peer| peer > cell) {
+            if value_at(layout, board, peer) == Some(value) {
+                duplicates.push((cell_to_point(layout, cell), cell_to_point(layout, peer)));
+            }
+        }
+    }
+    duplicates
+}
+
+/// Removes the values of filled cells from their peers, then keeps filling cells
+/// with a single possible value until an empty cell has no possible value left
+fn find_dead_cells(layout: &Layout, board: &SudokuBoard) -> Vec<Point<usize>> {
+    let mut values = vec![0u8; layout.cell_count()];
+    let mut candidates = vec![layout.all_values(); layout.cell_count()];
+    for (cell, cell_value) in values.iter_mut().enumerate() {
+        if let Some(value) = value_at(layout, board, cell) {
+            *cell_value = value.get();
+            for &peer in layout.peers(cell) {
+                candidates[peer] &= !value_to_mask(value.get());
+            }
+        }
+    }
+
+    loop {
+        let dead: Vec<Point<usize>> = (0..layout.cell_count())
+            .filter(|&cell| values[cell] == 0 && candidates[cell] == 0)
+            .map(|cell| cell_to_point(layout, cell))
+            .collect();
+        if !dead.is_empty() {
+            return dead;
+        }
+
+        let Some(forced) = (0..layout.cell_count()).find(|&cell| values[cell] == 0 && candidates[cell].count_ones() == 1) else {
+            return dead;
+        };
+        values[forced] = candidates[forced].trailing_zeros() as u8 + 1;
+        for &peer in layout.peers(forced) {
+            candidates[peer] &= !candidates[forced];
+        }
+    }
+}
+
+fn is_solvable(layout: &Arc<Layout>, board: &SudokuBoard, meter: &mut Meter) -> Result<bool, Interrupted> {
+    match SudokuSolver::with_layout(Arc::clone(layout), board) {
+        Ok(solver) => Ok(solver.count_solutions_metered(1, meter)? > 0),
+        Err(_) => Ok(false),
+    }
+}
+
+/// Finds a small set of entries made by the player whose removal makes the board solvable.
+/// Empty if the board isn't solvable even without any of them, fails if the meter runs out first.
+fn find_culprits(layout: &Arc<Layout>, board: &SudokuBoard, read_only: &[Vec<bool>], meter: &mut Meter) -> Result<Vec<Point<usize>>, Interrupted> {
+    let duplicates = find_duplicates(layout, board);
+    let point = |cell: usize| cell_to_point(layout, cell);
+    let is_duplicate = |cell: usize| duplicates.iter().any(|&(a, b)| a == point(cell) || b == point(cell));
+
+    // Entries that repeat a value are the most likely to be wrong, so they are tried first
+    let mut entries: Vec<usize> = (0..layout.cell_count())
+        .filter(|&cell| value_at(layout, board, cell).is_some() && !read_only[point(cell).y][point(cell).x])
+        .collect();
+    entries.sort_by_key(|&cell| !is_duplicate(cell));
+
+    let mut without_entries = board.clone();
+    for &cell in &entries {
+        without_entries[point(cell).y][point(cell).x] = None;
+    }
+    if !is_solvable(layout, &without_entries, meter)? {
+        return Ok(Vec::new());
+    }
+
+    // Usually a single wrong entry is to blame
+    for &cell in &entries {
+        let mut candidate = board.clone();
+        candidate[point(cell).y][point(cell).x] = None;
+        if is_solvable(layout, &candidate, meter)? {
+            return Ok(vec![point(cell)]);
+        }
+    }
+
+    // Otherwise put the entries back one by one, least suspicious first,
+    // leaving out those the board can't be solved with
+    let mut culprits = Vec::new();
+    let mut partial = without_entries;
+    for &cell in entries.iter().rev() {
+        partial[point(cell).y][point(cell).x] = value_at(layout, board, cell);
+        if !is_solvable(layout, &partial, meter)? {
+            partial[point(cell).y][point(cell).x] = None;
+            culprits.push(point(cell));
+        }
+    }
+    Ok(culprits)
+}
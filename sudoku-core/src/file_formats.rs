@@ -0,0 +1,171 @@
+//! Puzzle files: SadMan Software `.sdk` (one puzzle with metadata headers) and `.sdm` (one puzzle per line),
+//! and Simple Sudoku `.ss` grids
+
+use super::layout::Shape;
+use super::line_format::{board_to_line, parse_boards, value_symbol};
+use super::metadata::PuzzleMetadata;
+use super::sudoku::SudokuBoard;
+use super::sudoku_errors::ParseError;
+
+/// Header codes of `.sdk` files, the text of the header follows the code on the same line
+const SDK_AUTHOR: char = 'A';
+const SDK_DESCRIPTION: char = 'D';
+const SDK_COMMENT: char = 'C';
+const SDK_SOURCE: char = 'S';
+const SDK_LEVEL: char = 'L';
+
+/// Reads a `.sdk` file: `#` header lines followed by a grid with `.` for empty cells, usually 9x9. <br>
+/// The grid may sit in a `[Puzzle]` section, any later section (such as a saved `[State]`) is ignored.
+/// Headers other than author, description, comment, source and level are skipped.
+pub fn parse_sdk(text: &str) -> Result<(SudokuBoard, PuzzleMetadata), ParseError> {
+    let mut metadata = PuzzleMetadata::default();
+    // Lines that aren't part of the grid are blanked, so errors still point at the right line
+    let mut grid = String::with_capacity(text.len());
+    let mut in_puzzle = true;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(section) = trimmed.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            in_puzzle = section.eq_ignore_ascii_case("puzzle");
+        } else if let Some(header) = trimmed.strip_prefix('#') {
+            let mut chars = header.chars();
+            let code = chars.next();
+            let value = Some(chars.as_str().trim().to_string()).filter(|value| !value.is_empty());
+            match code {
+                Some(SDK_AUTHOR) => metadata.author = value,
+                Some(SDK_DESCRIPTION) => metadata.description = value,
+                Some(SDK_COMMENT) => metadata.comment = value,
+                Some(SDK_SOURCE) => metadata.source = value,
+                Some(SDK_LEVEL) => metadata.difficulty = value,
+                _ => {}
+            }
+        } else if in_puzzle {
+            grid.push_str(line);
+        }
+        grid.push('\n');
+    }
+
+    let board = parse_boards(&grid, 1)?.remove(0);
+    Ok((board, metadata))
+}
+
+/// Writes a `.sdk` file: a header line for every metadata field that is set, then the grid
+pub fn write_sdk(board: &SudokuBoard, metadata: &PuzzleMetadata) -> String {
+    let mut text = String::new();
+    let headers = [
+        (SDK_AUTHOR, &metadata.author),
+        (SDK_DESCRIPTION, &metadata.description),
+        (SDK_COMMENT, &metadata.comment),
+        (SDK_SOURCE, &metadata.source),
+        (SDK_LEVEL, &metadata.difficulty),
+    ];
+    for (code, value) in headers {
+        if let Some(value) = value {
+            // Headers are single lines
+            text.push_str(&format!("#{code}{}\n", value.replace(['\r', '\n'], " ")));
+        }
+    }
+    for row in board {
+        text.extend(row.iter().map(|cell| cell.map_or('.', value_symbol)));
+        text.push('\n');
+    }
+    text
+}
+
+/// Reads a `.sdm` file: one puzzle per line, e.g. of 81 characters, blank lines are skipped
+pub fn parse_sdm(text: &str) -> Result<Vec<SudokuBoard>, ParseError> {
+    let mut boards = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let board = parse_boards(line, 1).map_err(|error| ParseError { line: index + 1, ..error })?.remove(0);
+        boards.push(board);
+    }
+    Ok(boards)
+}
+
+/// Writes a `.sdm` file, one puzzle per line
+pub fn write_sdm<'a>(boards: impl IntoIterator<Item = &'a SudokuBoard>) -> String {
+    boards.into_iter().map(|board| board_to_line(board) + "\n").collect()
+}
+
+/// Reads a Simple Sudoku `.ss` grid: rows like `...|...|...` with `-----------` lines between the boxes.
+/// The `*-----------*` frame some files draw around the grid is accepted too.
+pub fn parse_ss(text: &str) -> Result<SudokuBoard, ParseError> {
+    // Frame corners are swapped for spaces so errors keep their columns
+    let grid = text.replace('*', " ");
+    Ok(parse_boards(&grid, 1)?.remove(0))
+}
+
+/// Writes a Simple Sudoku `.ss` grid, with the boxes of the shape
+pub fn write_ss(board: &SudokuBoard, shape: Shape) -> String {
+    let mut text = String::new();
+    let separator = "-".repeat(shape.size() + shape.size() / shape.box_width() - 1);
+    for (y, row) in board.iter().enumerate() {
+        if y != 0 && y % shape.box_height() == 0 {
+            text.push_str(&separator);
+            text.push('\n');
+        }
+        for (x, cell) in row.iter().enumerate() {
+            if x != 0 && x % shape.box_width() == 0 {
+                text.push('|');
+            }
+            text.push(cell.map_or('.', value_symbol));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str = "...86..3.8..9....2....71.9..2.....761.....2..5....8...3..7.45.............72..94.";
+
+    fn board() -> SudokuBoard {
+        parse_boards(PUZZLE, 1).unwrap().remove(0)
+    }
+
+    #[test]
+    fn sdk_round_trip() {
+        let metadata = PuzzleMetadata {
+            author: Some(String::from("Jane")),
+            description: Some(String::from("A test puzzle")),
+            source: Some(String::from("The generator")),
+            difficulty: Some(String::from("Hard")),
+            comment: Some(String::from("Two\nlines")),
+        };
+        let text = write_sdk(&board(), &metadata);
+        assert!(text.starts_with("#AJane\n"));
+        let (read, read_metadata) = parse_sdk(&text).unwrap();
+        assert_eq!(read, board());
+        assert_eq!(read_metadata, PuzzleMetadata { comment: Some(String::from("Two lines")), ..metadata });
+    }
+
+    #[test]
+    fn sdk_skips_later_sections() {
+        let text = format!("[Puzzle]\n{}[State]\n{}", write_sdk(&board(), &PuzzleMetadata::default()), "1".repeat(81));
+        assert_eq!(parse_sdk(&text).unwrap(), (board(), PuzzleMetadata::default()));
+    }
+
+    #[test]
+    fn sdm_round_trip() {
+        let mut other = board();
+        other[8][8] = None;
+        let text = write_sdm([&board(), &other]);
+        assert_eq!(text.lines().count(), 2);
+        assert_eq!(parse_sdm(&format!("\n{text}\n")).unwrap(), [board(), other]);
+    }
+
+    #[test]
+    fn ss_round_trip() {
+        let text = write_ss(&board(), Shape::CLASSIC);
+        assert_eq!(text.lines().next(), Some("...|86.|.3."));
+        assert_eq!(text.lines().nth(3), Some("-----------"));
+        assert_eq!(parse_ss(&text).unwrap(), board());
+
+        let framed = format!("*-----------*\n{}*-----------*\n", text.lines().map(|line| format!("|{line}|\n")).collect::<String>());
+        assert_eq!(parse_ss(&framed).unwrap(), board());
+    }
+}
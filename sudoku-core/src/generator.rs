@@ -0,0 +1,383 @@
+use std::sync::Arc;
+
+use rand::prelude::SliceRandom;
+use rand::Rng;
+
+use super::budget::Budget;
+use super::constraint::Cage;
+use super::layout::{Layout, Shape};
+use super::rating::{Grade, Rating};
+use super::sudoku::{Sudoku, SudokuBoard};
+use super::sudoku_errors::{GenerateError, SolveError};
+use super::sudoku_solver::SudokuSolver;
+
+/// Attempts at a graded puzzle before settling for the closest one found
+const MAX_GRADED_ATTEMPTS: usize = 100;
+
+/// Guesses allowed to prove that an emptied cell keeps the solution unique, the cell is filled back in otherwise. <br>
+/// Classic puzzles never need this many, but on 16x16 and bigger boards the search can take minutes.
+const MAX_UNIQUENESS_ITERATIONS: u64 = 5_000;
+
+/// Guesses allowed to prove that merging a Killer Sudoku cage of a single cell keeps the solution unique,
+/// once every cell was tried. There are only a few of those cages left by then, so they get a bigger budget.
+const MAX_LAST_PASS_ITERATIONS: u64 = 50_000;
+
+/// Guesses allowed to fill a grid before starting again with other random values. <br>
+/// Filling a classic grid takes a few dozen, but on some Jigsaw boards an unlucky guess early on can cost millions.
+const MAX_FILL_ITERATIONS: u64 = 10_000;
+
+/// Fills in a row that run out of guesses before the generator gives up on the layout. <br>
+/// A fill that proves there is no grid at all gives up right away.
+const MAX_FAILED_FILLS: usize = 100;
+
+/// What kind of puzzle to generate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PuzzleTarget {
+    /// Empty as many cells as possible, up to this many
+    EmptyCells(usize),
+    /// Keep emptying cells while the puzzle is at most this grade, and retry until it is exactly this grade
+    Grade(Grade),
+    /// Killer Sudoku without givens: starting with a cage for every cell, neighbouring cages are merged
+    /// into cages of up to this many cells while the solution stays unique. <br>
+    /// Cages of a single cell get a second try with one cell more, but proving the solution unique takes too long
+    /// for some of them, so a few are usually left on 9x9 boards and many on the biggest ones.
+    Cages(usize),
+}
+
+/// A filled grid being emptied one cell at a time
+struct Dig {
+    puzzle: SudokuBoard,
+    /// Cells not tried yet, in the random order they will be tried in
+    points: Vec<(usize, usize)>,
+    removed: usize,
+    /// Cells of the Killer Sudoku cages, emptied when merged into another one, see `PuzzleTarget::Cages`
+    cages: Vec<Vec<usize>>,
+    /// For every cell the index of the cage holding it
+    cage_of: Vec<usize>,
+    /// Cells left in cages of their own get a second chance once every cell was tried,
+    /// with a bigger budget to prove the solution stays unique
+    last_pass: bool,
+}
+
+/// Generates a puzzle in small steps, so a frontend can do other work in between. <br>
+/// Every puzzle it gives out has exactly one solution.
+pub struct PuzzleGenerator<R: Rng> {
+    target: PuzzleTarget,
+    layout: Arc<Layout>,
+    rng: R,
+    dig: Option<Dig>,
+    attempts: usize,
+    /// Fills in a row that took too long, see `MAX_FAILED_FILLS`
+    failed_fills: usize,
+    /// Hardest graded puzzle found so far that is below the target grade
+    closest: Option<(SudokuBoard, Grade)>,
+}
+
+impl<R: Rng> PuzzleGenerator<R> {
+    /// Creates a generator of classic 9x9 puzzles taking its randomness from `rng`
+    pub fn new(target: PuzzleTarget, rng: R) -> Self {
+        PuzzleGenerator {
+            target,
+            layout: Layout::shared(Shape::CLASSIC),
+            rng,
+            dig: None,
+            attempts: 0,
+            failed_fills: 0,
+            closest: None,
+        }
+    }
+
+    /// Generates puzzles of the layout instead, e.g. `Layout::shared(shape)` for another board size. <br>
+    /// Grading rates the puzzle after every emptied cell, which gets slow on boards above 9x9.
+    pub fn with_layout(mut self, layout: Arc<Layout>) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Does a small piece of work: fills a new grid or tries to empty one of its cells. <br>
+    /// Returns the puzzle once it is finished, calling `step` again afterwards starts on another puzzle. <br>
+    /// Fails with `GenerateError::NoGrid` if no grid fits the layout.
+    pub fn step(&mut self) -> Result<Option<Sudoku>, GenerateError> {
+        let max_removed = self.max_removed();
+        let Some(dig) = &mut self.dig else {
+            self.dig = self.start_dig()?;
+            return Ok(None);
+        };
+
+        if let PuzzleTarget::Cages(max_cells) = self.target {
+            // Cells in the smallest cages go first, so few cells are left in cages of their own
+            let size = self.layout.size();
+            let smallest = (0..dig.points.len()).rev().min_by_key(|&index| {
+                let (x, y) = dig.points[index];
+                dig.cages[dig.cage_of[y * size + x]].len()
+            });
+            if let Some(index) = smallest {
+                let (x, y) = dig.points.swap_remove(index);
+                let max_cells = if dig.last_pass { max_cells + 1 } else { max_cells };
+                dig.merge_cage(y * size + x, max_cells, &self.layout, &mut self.rng);
+                return Ok(None);
+            }
+            if !dig.last_pass {
+                dig.last_pass = true;
+                dig.points = (0..size * size)
+                    .filter(|&cell| dig.cages[dig.cage_of[cell]].len() == 1)
+                    .map(|cell| (cell % size, cell / size))
+                    .collect();
+                dig.points.shuffle(&mut self.rng);
+                dig.points.truncate(size);
+                return Ok(None);
+            }
+        } else if dig.removed < max_removed {
+            if let Some((x, y)) = dig.points.pop() {
+                let value = dig.puzzle[y][x].take();
+                // Only keep the cell empty if the puzzle still has exactly one solution
+                if has_unique_solution(&self.layout, &dig.puzzle, MAX_UNIQUENESS_ITERATIONS) && self.target.accepts(&self.layout, &dig.puzzle) {
+                    dig.removed += 1;
+                } else {
+                    dig.puzzle[y][x] = value;
+                }
+                return Ok(None);
+            }
+        }
+
+        let Some(dig) = self.dig.take() else {
+            return Ok(None);
+        };
+        if let PuzzleTarget::Cages(_) = self.target {
+            self.attempts = 0;
+            let size = self.layout.size();
+            let layout = self.layout.as_ref().clone().with_cages(dig.killer_cages(None));
+            return Ok(Some(Sudoku::from_givens_in(Arc::new(layout), vec![vec![None; size]; size])));
+        }
+        let layout = Arc::clone(&self.layout);
+        Ok(self.finish_attempt(dig.puzzle).map(|puzzle| Sudoku::from_givens_in(layout, puzzle)))
+    }
+
+    /// Runs the generator until the puzzle is finished. <br>
+    /// Fails with `GenerateError::NoGrid` if no grid fits the layout, which only happens with Jigsaw regions.
+    pub fn generate(&mut self) -> Result<Sudoku, GenerateError> {
+        loop {
+            if let Some(sudoku) = self.step()? {
+                return Ok(sudoku);
+            }
+        }
+    }
+
+    /// Same as `generate`, but gives up once the budget runs out, every step counts as one iteration. <br>
+    /// The generator keeps its progress, so calling it again with a new budget carries on with the same puzzle.
+    pub fn generate_within(&mut self, budget: &Budget) -> Result<Sudoku, GenerateError> {
+        let mut meter = budget.start();
+        loop {
+            meter.tick()?;
+            if let Some(sudoku) = self.step()? {
+                return Ok(sudoku);
+            }
+        }
+    }
+
+    /// Number of grids filled for the current puzzle, graded puzzles may need several
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+
+    /// Fills a new grid to empty, `None` if the search for one took too long and has to start again. <br>
+    /// Fails once there is no grid at all or too many fills in a row took too long.
+    fn start_dig(&mut self) -> Result<Option<Dig>, GenerateError> {
+        let size = self.layout.size();
+        let mut points: Vec<(usize, usize)> = Vec::with_capacity(size * size);
+        for y in 0..size {
+            for x in 0..size {
+                points.push((x, y));
+            }
+        }
+        points.shuffle(&mut self.rng);
+
+        let budget = Budget::unlimited().with_iterations(MAX_FILL_ITERATIONS);
+        let puzzle = match SudokuSolver::empty(Arc::clone(&self.layout)).solve_random_within(&mut self.rng, &budget) {
+            Ok(puzzle) => puzzle,
+            Err(SolveError::NoSolution(_)) => return Err(GenerateError::NoGrid),
+            Err(SolveError::Interrupted(_)) => {
+                self.failed_fills += 1;
+                if self.failed_fills >= MAX_FAILED_FILLS {
+                    self.failed_fills = 0;
+                    return Err(GenerateError::NoGrid);
+                }
+                return Ok(None);
+            }
+        };
+        self.failed_fills = 0;
+        self.attempts += 1;
+        Ok(Some(Dig {
+            puzzle,
+            points,
+            removed: 0,
+            cages: (0..size * size).map(|cell| vec![cell]).collect(),
+            cage_of: (0..size * size).collect(),
+            last_pass: false,
+        }))
+    }
+
+    fn max_removed(&self) -> usize {
+        match self.target {
+            PuzzleTarget::EmptyCells(count) => count.min(self.layout.cell_count()),
+            PuzzleTarget::Grade(_) => self.layout.cell_count(),
+            PuzzleTarget::Cages(_) => 0,
+        }
+    }
+
+    /// Decides if the dug puzzle is the final one, otherwise another attempt follows
+    fn finish_attempt(&mut self, puzzle: SudokuBoard) -> Option<SudokuBoard> {
+        let PuzzleTarget::Grade(grade) = self.target else {
+            self.attempts = 0;
+            return Some(puzzle);
+        };
+
+        let found = Rating::with_layout(Arc::clone(&self.layout), &puzzle).map(|r| r.grade).unwrap_or(Grade::Easy);
+        if found == grade {
+            self.attempts = 0;
+            self.closest = None;
+            return Some(puzzle);
+        }
+        if self.closest.as_ref().is_none_or(|(_, closest_grade)| found > *closest_grade) {
+            self.closest = Some((puzzle, found));
+        }
+        if self.attempts < MAX_GRADED_ATTEMPTS {
+            return None;
+        }
+        self.attempts = 0;
+        self.closest.take().map(|(puzzle, _)| puzzle)
+    }
+}
+
+impl PuzzleTarget {
+    /// Checks if a partly dug puzzle may stay the way it is
+    fn accepts(&self, layout: &Arc<Layout>, board: &SudokuBoard) -> bool {
+        match self {
+            PuzzleTarget::EmptyCells(_) | PuzzleTarget::Cages(_) => true,
+            // Every grade is at most Extreme, so there is no need to rate while digging
+            PuzzleTarget::Grade(Grade::Extreme) => true,
+            PuzzleTarget::Grade(grade) => Rating::with_layout(Arc::clone(layout), board).is_ok_and(|r| r.grade <= *grade),
+        }
+    }
+}
+
+impl Dig {
+    /// Merges the cage holding the cell with the first neighbouring one, in random order, that keeps the solution unique.
+    /// The merged cage can have at most `max_cells` cells, all with different values.
+    fn merge_cage(&mut self, cell: usize, max_cells: usize, layout: &Arc<Layout>, rng: &mut impl Rng) {
+        let size = layout.size();
+        let cage = self.cage_of[cell];
+        let values = |cells: &[usize]| cells.iter().fold(0u32, |mask, &cell| mask | 1 << self.value(cell, size));
+        let mut neighbours: Vec<usize> = self.cages[cage].iter()
+            .flat_map(|&cell| {
+                let (x, y) = (cell % size, cell / size);
+                [(x > 0).then(|| cell - 1), (x + 1 < size).then(|| cell + 1), (y > 0).then(|| cell - size), (y + 1 < size).then(|| cell + size)]
+            })
+            .flatten()
+            .map(|neighbour| self.cage_of[neighbour])
+            .filter(|&other| other != cage)
+            .collect();
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        neighbours.shuffle(rng);
+        let fitting = neighbours.into_iter().filter(|&other| {
+            self.cages[cage].len() + self.cages[other].len() <= max_cells
+                && values(&self.cages[cage]) & values(&self.cages[other]) == 0
+        });
+        let iterations = if self.last_pass { MAX_LAST_PASS_ITERATIONS } else { MAX_UNIQUENESS_ITERATIONS };
+        for other in fitting.collect::<Vec<_>>() {
+            let merged = layout.as_ref().clone().with_cages(self.killer_cages(Some((cage, other))));
+            if has_unique_solution(&Arc::new(merged), &vec![vec![None; size]; size], iterations) {
+                let moved = std::mem::take(&mut self.cages[other]);
+                for &cell in &moved {
+                    self.cage_of[cell] = cage;
+                }
+                self.cages[cage].extend(moved);
+                return;
+            }
+        }
+    }
+
+    /// The cages with their sums, with the second cage of `merged` added to the first one
+    fn killer_cages(&self, merged: Option<(usize, usize)>) -> Vec<Cage> {
+        let size = self.puzzle.len();
+        let mut cages: Vec<Vec<usize>> = self.cages.clone();
+        if let Some((cage, other)) = merged {
+            let moved = std::mem::take(&mut cages[other]);
+            cages[cage].extend(moved);
+        }
+        cages.into_iter()
+            .filter(|cells| !cells.is_empty())
+            .map(|cells| {
+                let sum = cells.iter().map(|&cell| self.value(cell, size) as u32).sum();
+                Cage::new(cells, sum)
+            })
+            .collect()
+    }
+
+    /// Value of the cell in the filled grid
+    fn value(&self, cell: usize, size: usize) -> u8 {
+        self.puzzle[cell / size][cell % size].map_or(0, |value| value.get())
+    }
+}
+
+fn has_unique_solution(layout: &Arc<Layout>, board: &SudokuBoard, iterations: u64) -> bool {
+    let budget = Budget::unlimited().with_iterations(iterations);
+    SudokuSolver::with_layout(Arc::clone(layout), board).is_ok_and(|s| s.count_solutions_within(2, &budget) == Ok(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::parse_region_map;
+
+    #[test]
+    fn puzzles_have_a_single_solution() {
+        let mut generator = PuzzleGenerator::new(PuzzleTarget::EmptyCells(50), StdRng::seed_from_u64(1));
+        let sudoku = generator.generate().unwrap();
+        assert!(sudoku.has_unique_solution());
+        assert!(sudoku.to_line().matches('.').count() <= 50);
+    }
+
+    #[test]
+    fn killer_puzzles_have_cages_instead_of_givens() {
+        let layout = Layout::shared(Shape::new(3, 2).unwrap());
+        let mut generator = PuzzleGenerator::new(PuzzleTarget::Cages(4), StdRng::seed_from_u64(1)).with_layout(layout);
+        let sudoku = generator.generate().unwrap();
+        assert!(sudoku.is_empty());
+        assert!(sudoku.layout().cages().iter().all(|cage| cage.cells().len() <= 5));
+        assert!(sudoku.layout().cages().iter().any(|cage| cage.cells().len() > 1));
+        assert!(sudoku.has_unique_solution());
+    }
+
+    #[test]
+    fn gives_up_on_regions_no_grid_fits() {
+        // The classic boxes with two cells of row 3 swapped between the first two regions. The first three columns
+        // hold every value three times, the regions 4 and 7 once each, so the cell of region 2 in column 3 must repeat
+        // the value of the cell of region 1 outside those columns, which is on the same row
+        let regions = parse_region_map("\
+            111222333\n111222333\n112122333\n\
+            444555666\n444555666\n444555666\n\
+            777888999\n777888999\n777888999").unwrap();
+        let layout = Arc::new(Layout::jigsaw(&regions).unwrap());
+        let mut generator = PuzzleGenerator::new(PuzzleTarget::EmptyCells(40), StdRng::seed_from_u64(1)).with_layout(layout);
+        assert_eq!(generator.generate().unwrap_err(), GenerateError::NoGrid);
+    }
+
+    #[test]
+    fn generating_within_a_budget_carries_on() {
+        let mut generator = PuzzleGenerator::new(PuzzleTarget::EmptyCells(40), StdRng::seed_from_u64(1));
+        let step = Budget::unlimited().with_iterations(1);
+        let sudoku = loop {
+            match generator.generate_within(&step) {
+                Ok(sudoku) => break sudoku,
+                Err(error) => assert_eq!(error, GenerateError::Interrupted(crate::Interrupted::Timeout)),
+            }
+        };
+        assert!(sudoku.has_unique_solution());
+    }
+}
@@ -0,0 +1,119 @@
+use std::num::NonZeroU8;
+
+/// What the player did to cause an edit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EditKind {
+    /// A value was placed or removed
+    Cell,
+    /// A pencil mark was toggled or the notes of a cell were cleared
+    Note,
+    /// Every user entry was cleared
+    Clear,
+    /// The board was filled in by the solver
+    Solve,
+}
+
+impl EditKind {
+    /// Short lowercase description, e.g. for an "Undo clear" tooltip
+    pub fn name(&self) -> &'static str {
+        match self {
+            EditKind::Cell => "cell edit",
+            EditKind::Note => "note edit",
+            EditKind::Clear => "clear",
+            EditKind::Solve => "solve",
+        }
+    }
+}
+
+/// Value and notes of a single cell before and after an edit
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CellChange {
+    /// Column of the cell
+    pub x: usize,
+    /// Row of the cell
+    pub y: usize,
+    /// Value of the cell before the edit
+    pub value_before: Option<NonZeroU8>,
+    /// Value of the cell after the edit
+    pub value_after: Option<NonZeroU8>,
+    /// Notes mask of the cell before the edit
+    pub notes_before: u32,
+    /// Notes mask of the cell after the edit
+    pub notes_after: u32,
+}
+
+/// A single undoable step, covering every cell it changed
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Edit {
+    /// What caused the edit
+    pub kind: EditKind,
+    /// Every cell the edit changed
+    pub changes: Vec<CellChange>,
+}
+
+/// Undo and redo stacks of the edits made to a sudoku
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct History {
+    done: Vec<Edit>,
+    undone: Vec<Edit>,
+}
+
+impl History {
+    /// Creates an empty history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a new edit. Edits that changed nothing are ignored. <br>
+    /// Anything that was undone can't be redone after a new edit.
+    pub fn push(&mut self, edit: Edit) {
+        if edit.changes.is_empty() {
+            return;
+        }
+        self.done.push(edit);
+        self.undone.clear();
+    }
+
+    /// Moves the last edit to the redo stack and returns it, so its changes can be reverted
+    pub fn undo(&mut self) -> Option<Edit> {
+        let edit = self.done.pop()?;
+        self.undone.push(edit.clone());
+        Some(edit)
+    }
+
+    /// Moves the last undone edit back to the undo stack and returns it, so its changes can be reapplied
+    pub fn redo(&mut self) -> Option<Edit> {
+        let edit = self.undone.pop()?;
+        self.done.push(edit.clone());
+        Some(edit)
+    }
+
+    /// Whether there is an edit to undo
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    /// Whether there is an undone edit to redo
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Every edit that can be undone or redone, in no particular order
+    pub(crate) fn edits(&self) -> impl Iterator<Item = &Edit> {
+        self.done.iter().chain(&self.undone)
+    }
+
+    /// Kind of the edit `undo` would revert
+    pub fn next_undo(&self) -> Option<EditKind> {
+        self.done.last().map(|edit| edit.kind)
+    }
+
+    /// Kind of the edit `redo` would reapply
+    pub fn next_redo(&self) -> Option<EditKind> {
+        self.undone.last().map(|edit| edit.kind)
+    }
+}
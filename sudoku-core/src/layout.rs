@@ -0,0 +1,539 @@
+//! Board shapes, their constraints and the index tables derived from them. Cells are indexed by `y * size + x`.
+
+use std::collections::HashMap;
+use std::num::NonZeroU8;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use super::cell::all_values;
+use super::constraint::{AllDifferent, Cage, Constraint};
+use super::point::Point;
+use super::sudoku_errors::RegionError;
+
+/// Largest number of values a board can have, values above 9 are written as the letters `A` to `P`
+pub const MAX_SIZE: usize = 25;
+
+/// Dimensions of the boxes (regions) of a board. <br>
+/// A board has as many rows, columns and values as a box has cells, e.g. 2x3 boxes make a 6x6 board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shape {
+    box_width: usize,
+    box_height: usize,
+}
+
+impl Shape {
+    /// The classic 9x9 board with 3x3 boxes
+    pub const CLASSIC: Shape = Shape { box_width: 3, box_height: 3 };
+
+    /// The usual board sizes: 4x4, 6x6, 9x9, 12x12, 16x16 and 25x25
+    pub const ALL: [Shape; 6] = [
+        Shape { box_width: 2, box_height: 2 },
+        Shape { box_width: 3, box_height: 2 },
+        Shape::CLASSIC,
+        Shape { box_width: 4, box_height: 3 },
+        Shape { box_width: 4, box_height: 4 },
+        Shape { box_width: 5, box_height: 5 },
+    ];
+
+    /// Creates a shape from the width and height of a box. <br>
+    /// Returns `None` if a box would hold fewer than 2 or more than `MAX_SIZE` cells.
+    pub fn new(box_width: usize, box_height: usize) -> Option<Self> {
+        let size = box_width * box_height;
+        (box_width > 0 && box_height > 0 && (2..=MAX_SIZE).contains(&size)).then_some(Shape { box_width, box_height })
+    }
+
+    /// The usual shape of a board with `size` rows: boxes as close to square as possible, wider than they are tall. <br>
+    /// Returns `None` for sizes without boxes at least two cells tall, like primes.
+    pub fn for_size(size: usize) -> Option<Self> {
+        let box_height = (2..=size).take_while(|height| height * height <= size).filter(|&height| size.is_multiple_of(height)).last()?;
+        Shape::new(size / box_height, box_height)
+    }
+
+    /// Number of columns in a box
+    pub fn box_width(&self) -> usize {
+        self.box_width
+    }
+
+    /// Number of rows in a box
+    pub fn box_height(&self) -> usize {
+        self.box_height
+    }
+
+    /// Number of rows, columns and values of the board
+    pub fn size(&self) -> usize {
+        self.box_width * self.box_height
+    }
+
+    /// Number of cells of the board
+    pub fn cell_count(&self) -> usize {
+        self.size() * self.size()
+    }
+}
+
+impl Default for Shape {
+    fn default() -> Self {
+        Shape::CLASSIC
+    }
+}
+
+/// Prints the board size, e.g. "16x16"
+impl std::fmt::Display for Shape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}", self.size(), self.size())
+    }
+}
+
+/// The constraints of a board, by default its rows, columns and regions, with the units and peers of every cell
+/// derived from them. Computed once per shape and shared between the boards and solvers using it.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(try_from = "LayoutSpec"))]
+pub struct Layout {
+    shape: Shape,
+    /// Whether both main diagonals hold every value once too, as in Sudoku-X
+    diagonals: bool,
+    /// Whether the regions come from a region map instead of the boxes of the shape, as in Jigsaw Sudoku
+    irregular: bool,
+    /// Cages of Killer Sudoku, empty for the other variants
+    cages: Vec<Cage>,
+    /// Rows, then columns, then regions, then the diagonals, then the cages,
+    /// then the constraints added with `with_constraint`
+    constraints: Vec<Arc<dyn Constraint>>,
+    /// Number of constraints added with `with_constraint`, they come last
+    custom: usize,
+    /// Cells of the constraints whose values differ and cover as many cells as there are values:
+    /// rows, then columns, then regions, then the added ones
+    units: Vec<Vec<usize>>,
+    /// For every cell the indices of the units holding it
+    units_of: Vec<Vec<usize>>,
+    /// For every cell the cells that may not hold the same value, sorted
+    peers: Vec<Vec<usize>>,
+    /// For every cell the indices of the constraints holding it that prune more than the peers do
+    pruned_by: Vec<Vec<usize>>,
+    region_of: Vec<usize>,
+    /// For every cell the index of the cage holding it
+    cage_of: Vec<Option<usize>>,
+}
+
+impl Layout {
+    /// Computes the tables for the shape, prefer `Layout::shared` to reuse them
+    pub fn new(shape: Shape) -> Self {
+        Layout::build(shape, false, None, Vec::new(), Vec::new())
+    }
+
+    /// The Sudoku-X layout of the shape, where both main diagonals have to hold every value once too
+    pub fn diagonal(shape: Shape) -> Self {
+        Layout::build(shape, true, None, Vec::new(), Vec::new())
+    }
+
+    /// The Jigsaw layout with the regions of the map, the shape follows from its number of rows. <br>
+    /// Fails if there is no shape with that many rows, or for the reasons `with_regions` does.
+    ///
+    /// ```
+    /// use sudoku_core::{parse_region_map, Layout, Sudoku};
+    /// use std::sync::Arc;
+    ///
+    /// let map = parse_region_map("1122\n1122\n3344\n3344").unwrap();
+    /// let layout = Layout::jigsaw(&map).unwrap();
+    /// assert!(layout.has_irregular_regions());
+    /// let mut sudoku = Sudoku::with_layout(Arc::new(layout));
+    /// sudoku.solve().unwrap();
+    /// assert!(sudoku.check());
+    /// ```
+    pub fn jigsaw(regions: &[Vec<usize>]) -> Result<Self, RegionError> {
+        let shape = Shape::for_size(regions.len()).ok_or(RegionError::WrongSize(regions.len()))?;
+        Layout::new(shape).with_regions(regions)
+    }
+
+    /// Replaces the boxes with irregular regions, as in Jigsaw Sudoku: `regions[y][x]` is the region of the cell
+    /// in column `x` and row `y`, counted from 0. <br>
+    /// Fails unless the map has the size of the board and every region has as many cells as a row.
+    pub fn with_regions(self, regions: &[Vec<usize>]) -> Result<Self, RegionError> {
+        let size = self.size();
+        if let Some(row) = std::iter::once(regions.len()).chain(regions.iter().map(Vec::len)).find(|&len| len != size) {
+            return Err(RegionError::WrongSize(row));
+        }
+        let mut cells = vec![0; size];
+        for (y, row) in regions.iter().enumerate() {
+            for (x, &region) in row.iter().enumerate() {
+                *cells.get_mut(region).ok_or(RegionError::UnknownRegion(Point::new(x, y)))? += 1;
+            }
+        }
+        if let Some((region, &cells)) = cells.iter().enumerate().find(|&(_, &cells)| cells != size) {
+            return Err(RegionError::RegionSize { region, cells });
+        }
+        let custom = self.custom_constraints().to_vec();
+        Ok(Layout::build(self.shape, self.diagonals, Some(regions.concat()), self.cages, custom))
+    }
+
+    /// Turns the board into Killer Sudoku with the cages, replacing any it had. <br>
+    /// Panics if a cage covers a cell outside the board or of another cage, or has a sum its cells can't make.
+    ///
+    /// ```
+    /// use sudoku_core::{Cage, Layout, Shape};
+    ///
+    /// // The top left cells of a 4x4 board add up to 3, so they hold 1 and 2
+    /// let layout = Layout::new(Shape::new(2, 2).unwrap()).with_cages(vec![Cage::new(vec![0, 1], 3)]);
+    /// assert_eq!(layout.cage_at(1, 0), Some(0));
+    /// ```
+    pub fn with_cages(self, cages: Vec<Cage>) -> Self {
+        if let Err(error) = check_cages(self.shape, &cages) {
+            panic!("{error}");
+        }
+        let custom = self.custom_constraints().to_vec();
+        let regions = self.irregular.then_some(self.region_of);
+        Layout::build(self.shape, self.diagonals, regions, cages, custom)
+    }
+
+    /// Adds a rule the board has to follow on top of its rows, columns and regions. <br>
+    /// Panics if the constraint covers a cell outside the board.
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use sudoku_core::{AllDifferent, Layout, Shape, Sudoku};
+    ///
+    /// // The main diagonal has to hold every value once too
+    /// let diagonal = (0..9).map(|i| i * 10).collect();
+    /// let layout = Layout::new(Shape::CLASSIC).with_constraint(AllDifferent::new(diagonal));
+    /// let mut sudoku = Sudoku::with_layout(Arc::new(layout));
+    /// sudoku.solve().unwrap();
+    /// assert!(sudoku.check());
+    /// ```
+    pub fn with_constraint(self, constraint: impl Constraint + 'static) -> Self {
+        let cell_count = self.cell_count();
+        assert!(constraint.cells().iter().all(|&cell| cell < cell_count), "the constraint covers a cell outside the board");
+        let mut custom = self.custom_constraints().to_vec();
+        custom.push(Arc::new(constraint));
+        let regions = self.irregular.then_some(self.region_of);
+        Layout::build(self.shape, self.diagonals, regions, self.cages, custom)
+    }
+
+    /// Derives the tables, `regions` gives the region of every cell of a Jigsaw board instead of the boxes
+    fn build(shape: Shape, diagonals: bool, regions: Option<Vec<usize>>, cages: Vec<Cage>, custom: Vec<Arc<dyn Constraint>>) -> Self {
+        let size = shape.size();
+        let irregular = regions.is_some();
+        let region_of: Vec<usize> = regions.unwrap_or_else(|| (0..shape.cell_count())
+            .map(|cell| (cell / size) / shape.box_height * shape.box_height + (cell % size) / shape.box_width)
+            .collect());
+
+        let mut groups = vec![Vec::with_capacity(size); 3 * size];
+        for cell in 0..shape.cell_count() {
+            groups[cell / size].push(cell);
+            groups[size + cell % size].push(cell);
+            groups[2 * size + region_of[cell]].push(cell);
+        }
+        if diagonals {
+            groups.push((0..size).map(|i| i * size + i).collect());
+            groups.push((0..size).map(|i| i * size + size - 1 - i).collect());
+        }
+
+        let mut constraints: Vec<Arc<dyn Constraint>> = groups.into_iter()
+            .map(|cells| Arc::new(AllDifferent::new(cells)) as Arc<dyn Constraint>)
+            .collect();
+        let mut cage_of = vec![None; shape.cell_count()];
+        for (index, cage) in cages.iter().enumerate() {
+            for &cell in cage.cells() {
+                cage_of[cell] = Some(index);
+            }
+            constraints.push(Arc::new(cage.clone()));
+        }
+        let custom_count = custom.len();
+        constraints.extend(custom);
+
+        let mut units = Vec::new();
+        let mut units_of = vec![Vec::new(); shape.cell_count()];
+        let mut peers = vec![Vec::new(); shape.cell_count()];
+        let mut pruned_by = vec![Vec::new(); shape.cell_count()];
+        for (index, constraint) in constraints.iter().enumerate() {
+            let cells = constraint.cells();
+            if constraint.values_differ() {
+                if cells.len() == shape.size() {
+                    for &cell in cells {
+                        units_of[cell].push(units.len());
+                    }
+                    units.push(cells.to_vec());
+                }
+                for &cell in cells {
+                    peers[cell].extend(cells.iter().filter(|&&other| other != cell));
+                }
+            }
+            if constraint.prunes_beyond_peers() {
+                for &cell in cells {
+                    pruned_by[cell].push(index);
+                }
+            }
+        }
+        for cell_peers in &mut peers {
+            cell_peers.sort_unstable();
+            cell_peers.dedup();
+        }
+
+        Layout { shape, diagonals, irregular, cages, constraints, custom: custom_count, units, units_of, peers, pruned_by, region_of, cage_of }
+    }
+
+    /// The layout of the shape, computed on first use and shared afterwards
+    pub fn shared(shape: Shape) -> Arc<Layout> {
+        static LAYOUTS: OnceLock<Mutex<HashMap<Shape, Arc<Layout>>>> = OnceLock::new();
+        let mut layouts = LAYOUTS.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner());
+        layouts.entry(shape).or_insert_with(|| Arc::new(Layout::new(shape))).clone()
+    }
+
+    /// The shared layout of the usual shape for a board with `size` rows. Panics if there is none.
+    pub(crate) fn for_size(size: usize) -> Arc<Layout> {
+        let shape = Shape::for_size(size).unwrap_or_else(|| panic!("no board shape has {size} rows"));
+        Layout::shared(shape)
+    }
+
+    /// Shape of the boxes
+    pub fn shape(&self) -> Shape {
+        self.shape
+    }
+
+    /// Number of rows, columns and values of the board
+    pub fn size(&self) -> usize {
+        self.shape.size()
+    }
+
+    /// Number of cells of the board
+    pub fn cell_count(&self) -> usize {
+        self.shape.cell_count()
+    }
+
+    /// Index of the region holding the cell in column `x` and row `y`.
+    /// Boxes are counted from the top left, Jigsaw regions are numbered by their map.
+    pub fn region_at(&self, x: usize, y: usize) -> usize {
+        self.region_of[y * self.size() + x]
+    }
+
+    /// Whether the regions come from a region map instead of the boxes of the shape, see `Layout::with_regions`
+    pub fn has_irregular_regions(&self) -> bool {
+        self.irregular
+    }
+
+    /// The region of every cell as `regions[y][x]`, the map `with_regions` takes
+    pub fn region_map(&self) -> Vec<Vec<usize>> {
+        self.region_of.chunks(self.size()).map(<[usize]>::to_vec).collect()
+    }
+
+    /// Whether both main diagonals hold every value once too, see `Layout::diagonal`
+    pub fn has_diagonals(&self) -> bool {
+        self.diagonals
+    }
+
+    /// Checks if the cell in column `x` and row `y` is on a diagonal that has to hold every value once
+    pub fn is_on_diagonal(&self, x: usize, y: usize) -> bool {
+        self.diagonals && (x == y || x + y == self.size() - 1)
+    }
+
+    /// Cages of Killer Sudoku, see `Layout::with_cages`
+    pub fn cages(&self) -> &[Cage] {
+        &self.cages
+    }
+
+    /// Index into `cages` of the cage holding the cell in column `x` and row `y`
+    pub fn cage_at(&self, x: usize, y: usize) -> Option<usize> {
+        self.cage_of[y * self.size() + x]
+    }
+
+    /// Checks if the two cells may not hold the same value, e.g. because they share a row, column or region
+    pub fn are_peers(&self, a: Point<usize>, b: Point<usize>) -> bool {
+        self.sees(a.y * self.size() + a.x, b.y * self.size() + b.x)
+    }
+
+    /// Mask with a bit for every value, bit `n - 1` stands for the value `n`
+    pub fn all_values(&self) -> u32 {
+        all_values(self.size())
+    }
+
+    /// Every rule of the board: rows, then columns, then regions, then the diagonals, the cages and the added constraints
+    pub fn constraints(&self) -> &[Arc<dyn Constraint>] {
+        &self.constraints
+    }
+
+    /// Checks the values of a board indexed as `values[y][x]` against every constraint, empty cells are allowed
+    pub fn check(&self, values: &[Vec<Option<NonZeroU8>>]) -> bool {
+        let mut covered = Vec::with_capacity(self.size());
+        self.constraints.iter().all(|constraint| {
+            covered.clear();
+            covered.extend(constraint.cells().iter().map(|&cell| values[self.row_of(cell)][self.column_of(cell)]));
+            constraint.check(&covered)
+        })
+    }
+
+    /// The constraints on top of the rows, columns and regions, including the diagonals and cages
+    fn added_constraints(&self) -> &[Arc<dyn Constraint>] {
+        &self.constraints[3 * self.size()..]
+    }
+
+    /// The constraints added with `with_constraint`
+    fn custom_constraints(&self) -> &[Arc<dyn Constraint>] {
+        &self.constraints[self.constraints.len() - self.custom..]
+    }
+
+    /// Checks if there are rules on top of the rows, columns and regions
+    pub(crate) fn has_added_constraints(&self) -> bool {
+        !self.added_constraints().is_empty()
+    }
+
+    pub(crate) fn row_of(&self, cell: usize) -> usize {
+        cell / self.size()
+    }
+
+    pub(crate) fn column_of(&self, cell: usize) -> usize {
+        cell % self.size()
+    }
+
+    pub(crate) fn region_of(&self, cell: usize) -> usize {
+        self.region_of[cell]
+    }
+
+    /// Every unit: rows, then columns, then regions, then the units of the added constraints
+    pub(crate) fn units(&self) -> &[Vec<usize>] {
+        &self.units
+    }
+
+    /// Indices of the units holding the cell
+    pub(crate) fn units_of(&self, cell: usize) -> &[usize] {
+        &self.units_of[cell]
+    }
+
+    pub(crate) fn rows(&self) -> &[Vec<usize>] {
+        &self.units[..self.size()]
+    }
+
+    pub(crate) fn columns(&self) -> &[Vec<usize>] {
+        &self.units[self.size()..2 * self.size()]
+    }
+
+    pub(crate) fn regions(&self) -> &[Vec<usize>] {
+        &self.units[2 * self.size()..3 * self.size()]
+    }
+
+    /// The units of the added constraints, e.g. the diagonals
+    pub(crate) fn added_units(&self) -> &[Vec<usize>] {
+        &self.units[3 * self.size()..]
+    }
+
+    /// Cells that may not hold the same value as the cell, e.g. because they share a row, column or region
+    pub(crate) fn peers(&self, cell: usize) -> &[usize] {
+        &self.peers[cell]
+    }
+
+    /// Checks if two cells may not hold the same value
+    pub(crate) fn sees(&self, a: usize, b: usize) -> bool {
+        self.peers[a].binary_search(&b).is_ok()
+    }
+
+    /// Indices into `constraints` of the rules holding the cell that the solver has to prune with
+    pub(crate) fn pruned_by(&self, cell: usize) -> &[usize] {
+        &self.pruned_by[cell]
+    }
+}
+
+/// Layouts of the same shape and variant are equal if they have no constraints added with `with_constraint`,
+/// or share the same ones
+impl PartialEq for Layout {
+    fn eq(&self, other: &Self) -> bool {
+        self.shape == other.shape
+            && self.diagonals == other.diagonals
+            && self.region_of == other.region_of
+            && self.cages == other.cages
+            && self.custom == other.custom
+            && self.custom_constraints().iter().zip(other.custom_constraints()).all(|(a, b)| Arc::ptr_eq(a, b))
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout::new(Shape::CLASSIC)
+    }
+}
+
+/// Only the shape, the variant and the custom constraints are printed, the tables follow from them
+impl std::fmt::Debug for Layout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Layout")
+            .field("shape", &self.shape)
+            .field("diagonals", &self.diagonals)
+            .field("regions", &self.irregular.then(|| self.region_map()))
+            .field("cages", &self.cages)
+            .field("custom_constraints", &self.custom_constraints())
+            .finish()
+    }
+}
+
+/// What a layout is saved as, the tables are computed again when it is read
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct LayoutSpec {
+    #[serde(flatten)]
+    shape: Shape,
+    /// Games saved before Sudoku-X existed have no diagonals
+    #[serde(default)]
+    diagonals: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    regions: Option<Vec<Vec<usize>>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    cages: Vec<Cage>,
+}
+
+/// Fails for layouts with constraints added with `with_constraint`, which can't be rebuilt from a file
+#[cfg(feature = "serde")]
+impl serde::Serialize for Layout {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.custom > 0 {
+            return Err(serde::ser::Error::custom("layouts with custom constraints can't be saved"));
+        }
+        LayoutSpec {
+            shape: self.shape,
+            diagonals: self.diagonals,
+            regions: self.irregular.then(|| self.region_map()),
+            cages: self.cages.clone(),
+        }.serialize(serializer)
+    }
+}
+
+/// Fails for shapes `Shape::new` wouldn't create, or regions and cages `Layout::with_regions` and `Layout::with_cages`
+/// wouldn't take, e.g. read from a damaged save
+#[cfg(feature = "serde")]
+impl TryFrom<LayoutSpec> for Layout {
+    type Error = String;
+
+    fn try_from(spec: LayoutSpec) -> Result<Self, Self::Error> {
+        let LayoutSpec { shape, diagonals, regions, cages } = spec;
+        let shape = Shape::new(shape.box_width, shape.box_height)
+            .ok_or_else(|| format!("boxes of {}x{} cells are not supported", shape.box_width, shape.box_height))?;
+        let cages: Vec<Cage> = cages.into_iter().map(|cage| Cage::new(cage.cells().to_vec(), cage.sum())).collect();
+        check_cages(shape, &cages)?;
+        let layout = Layout::build(shape, diagonals, None, cages, Vec::new());
+        match regions {
+            Some(regions) => layout.with_regions(&regions).map_err(|e| e.to_string()),
+            None => Ok(layout),
+        }
+    }
+}
+
+/// Checks that the cages cover cells of the board, no cell twice, and that their cells can make their sums
+fn check_cages(shape: Shape, cages: &[Cage]) -> Result<(), String> {
+    let mut covered = vec![false; shape.cell_count()];
+    for cage in cages {
+        let count = cage.cells().len();
+        if count == 0 || count > shape.size() {
+            return Err(format!("a cage can't have {count} cells on a {shape} board"));
+        }
+        for &cell in cage.cells() {
+            if cell >= shape.cell_count() {
+                return Err(format!("cell {cell} of a cage is outside the board"));
+            }
+            if std::mem::replace(&mut covered[cell], true) {
+                return Err(format!("cell {cell} is in more than one cage"));
+            }
+        }
+        // The sums of the smallest and the largest values
+        let (count, size) = (count as u32, shape.size() as u32);
+        let triangle = count * (count - 1) / 2;
+        if cage.sum() < count + triangle || cage.sum() > count * size - triangle {
+            return Err(format!("{count} cells can't add up to {}", cage.sum()));
+        }
+    }
+    Ok(())
+}
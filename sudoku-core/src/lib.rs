@@ -0,0 +1,63 @@
+//! Sudoku engine used by the sudoku-solver GUI, usable on its own without Dioxus.
+//!
+//! The crate covers the whole game model:
+//! * [`Sudoku`] - a game in progress: givens, user entries, pencil marks and undo/redo history,
+//!   plus puzzle generation, hints, rating and validation
+//! * [`SudokuSolver`] - the backtracking solver used for solving, counting solutions and
+//!   generating full grids, reporting its steps as [`SolverEvent`]s if asked to
+//! * [`LogicalSolver`] - a human-style solver that applies named [`Technique`]s
+//! * [`Shape`] and [`Layout`] - the size of the board and its boxes, from 4x4 up to 25x25,
+//!   or irregular regions read with [`parse_region_map`] for Jigsaw Sudoku
+//! * [`Constraint`] - a rule of the board, the solver and the validator follow every constraint of the layout,
+//!   so variants can add their own, e.g. the [`Cage`]s of Killer Sudoku
+//! * [`Point`], [`Cell`] and the error type [`ErrorNoSolution`]
+//! * [`Budget`] and [`CancelToken`] - limits for searches that might run for too long, see [`SudokuSolver::solve_within`]
+//!
+//! Boards are indexed as `board[y][x]`, with `None` for empty cells. Classic boards are 9x9,
+//! values above 9 on larger boards are written as letters, see [`value_symbol`].
+//!
+//! ```
+//! use sudoku_core::Sudoku;
+//!
+//! let mut sudoku = Sudoku::new_puzzle(40);
+//! assert!(sudoku.has_unique_solution());
+//! sudoku.solve().unwrap();
+//! assert!(sudoku.check());
+//! ```
+#![warn(missing_docs)]
+
+mod sudoku;
+mod sudoku_solver;
+mod logical_solver;
+mod techniques;
+mod solve_step;
+mod rating;
+mod history;
+mod diagnostics;
+mod layout;
+mod constraint;
+mod cell;
+mod sudoku_errors;
+mod line_format;
+mod file_formats;
+mod metadata;
+mod generator;
+mod budget;
+mod solver_event;
+
+pub mod point;
+pub use sudoku::*;
+pub use sudoku_solver::SudokuSolver;
+pub use logical_solver::{LogicalOutcome, LogicalSolver, Technique};
+pub use solve_step::{SolutionSteps, SolveStep, StepAction};
+pub use rating::{Grade, Rating};
+pub use history::{CellChange, Edit, EditKind, History};
+pub use cell::{Cell, UncollapsedCell};
+pub use layout::{Layout, Shape, MAX_SIZE};
+pub use constraint::{AllDifferent, Cage, Constraint};
+pub use line_format::{parse_region_map, parse_value, region_map_to_text, value_symbol};
+pub use point::Point;
+pub use metadata::PuzzleMetadata;
+pub use generator::{PuzzleGenerator, PuzzleTarget};
+pub use budget::{Budget, CancelToken};
+pub use solver_event::{SolverEvent, SolverObserver};
@@ -0,0 +1,266 @@
+//! The line format used by most puzzle collections, one character per cell in reading order
+//! with `.` or `0` for empty cells, e.g. 81 characters for a 9x9 puzzle. <br>
+//! Values above 9 are written as the letters `A` to `P`, so a 16x16 puzzle uses `1`-`9` and `A`-`G`.
+//! The region maps of Jigsaw boards are written the same way, with the region of every cell counted from `1`.
+
+use std::num::NonZeroU8;
+
+use super::layout::{Shape, MAX_SIZE};
+use super::sudoku::SudokuBoard;
+use super::sudoku_errors::{ParseError, ParseErrorKind, RegionError};
+
+/// Character standing for the value: `1`-`9`, then `A` for 10 up to `P` for 25
+pub fn value_symbol(value: NonZeroU8) -> char {
+    match value.get() {
+        value @ 1..=9 => char::from(b'0' + value),
+        value => char::from(b'A' + value - 10),
+    }
+}
+
+/// Value written as the character, the reverse of `value_symbol`. Letters may be lowercase.
+pub fn parse_value(c: char) -> Option<NonZeroU8> {
+    let value = match c.to_ascii_uppercase() {
+        c @ '1'..='9' => c as u8 - b'0',
+        c @ 'A'..='Z' => c as u8 - b'A' + 10,
+        _ => return None,
+    };
+    NonZeroU8::new(value).filter(|value| usize::from(value.get()) <= MAX_SIZE)
+}
+
+/// A cell read from a line of text, with the column and character it was written as
+struct LineCell {
+    column_index: usize,
+    symbol: char,
+    value: Option<NonZeroU8>,
+}
+
+/// The cells written on the line, whitespace and decorations skipped. <br>
+/// Fails with the column of the first character that doesn't stand for a cell.
+fn read_line(line: &str) -> Result<Vec<LineCell>, (char, usize)> {
+    let mut cells = Vec::new();
+    for (column_index, symbol) in line.chars().enumerate() {
+        let value = match symbol {
+            '.' | '0' => None,
+            '|' | '+' | '-' => continue,
+            c if c.is_whitespace() => continue,
+            c => Some(parse_value(c).ok_or((c, column_index))?),
+        };
+        cells.push(LineCell { column_index, symbol, value });
+    }
+    Ok(cells)
+}
+
+/// Works out the size of a puzzle from the cells on its first line: a whole puzzle, a single row,
+/// or several rows of a classic puzzle. <br>
+/// 16 cells could be a whole 4x4 puzzle as well as a row of a 16x16 one, they are taken for a row
+/// if the line has `|` decorations or a value above 4, or if with the next lines holding cells they make
+/// 16 lines of 16 cells with a value above 4 among them.
+fn infer_size(cells: &[LineCell], decorated: bool, next_lines: impl Iterator<Item = Vec<LineCell>>) -> Option<usize> {
+    let largest_value = |cells: &[LineCell]| cells.iter().filter_map(|cell| cell.value).map(|value| usize::from(value.get())).max().unwrap_or(0);
+    let count = cells.len();
+    let side = (1..=MAX_SIZE).find(|side| side * side == count);
+    let rows_of_larger_puzzle = |side: usize| {
+        let rows: Vec<_> = next_lines.take(count - 1).collect();
+        rows.len() == count - 1 && rows.iter().all(|row| row.len() == count) && rows.iter().any(|row| largest_value(row) > side)
+    };
+    match side {
+        Some(side) if !decorated && largest_value(cells) <= side && Shape::for_size(side).is_some()
+            && !(Shape::for_size(count).is_some() && rows_of_larger_puzzle(side)) => Some(side),
+        _ if Shape::for_size(count).is_some() => Some(count),
+        _ if count.is_multiple_of(9) => Some(9),
+        _ => None,
+    }
+}
+
+/// Reads the puzzles in `text`, stopping with an error if there are more than `limit`. <br>
+/// A puzzle can be written on one line or spread over several lines, but every line has to hold whole rows.
+/// The first line of every puzzle tells its size, see `infer_size`.
+/// Whitespace and the `|`, `+` and `-` decorations of the grid printed by `Display for Sudoku` are skipped.
+pub fn parse_boards(text: &str, limit: usize) -> Result<Vec<SudokuBoard>, ParseError> {
+    let mut boards = Vec::new();
+    let mut cells: Vec<Option<NonZeroU8>> = Vec::new();
+    let mut puzzle_size = None;
+    let mut last_position = (1, 1);
+    let lines: Vec<&str> = text.lines().collect();
+
+    for (line_index, line) in lines.iter().enumerate() {
+        let error = |kind, column_index: usize| ParseError { kind, line: line_index + 1, column: column_index + 1 };
+        let line_cells = read_line(line).map_err(|(c, column_index)| error(ParseErrorKind::InvalidCharacter(c), column_index))?;
+        if line_cells.is_empty() {
+            last_position = (line_index + 1, line.chars().count() + 1);
+            continue;
+        }
+
+        let size = match puzzle_size {
+            Some(size) => size,
+            None => {
+                let next_lines = lines[line_index + 1..].iter()
+                    .map_while(|line| read_line(line).ok())
+                    .filter(|cells| !cells.is_empty());
+                *puzzle_size.insert(infer_size(&line_cells, line.contains('|'), next_lines)
+                    .ok_or_else(|| error(ParseErrorKind::UnknownSize(line_cells.len()), line_cells[0].column_index))?)
+            },
+        };
+        let mut completed_in_line = false;
+        for &LineCell { column_index, symbol, value } in &line_cells {
+            if completed_in_line || boards.len() == limit {
+                return Err(error(ParseErrorKind::TooManyCells, column_index));
+            }
+            if value.is_some_and(|value| usize::from(value.get()) > size) {
+                return Err(error(ParseErrorKind::InvalidCharacter(symbol), column_index));
+            }
+            cells.push(value);
+            if cells.len() == size * size {
+                boards.push(cells.chunks(size).map(|row| row.to_vec()).collect());
+                cells.clear();
+                completed_in_line = true;
+            }
+        }
+        let line_end = error(ParseErrorKind::IncompleteRow(line_cells.len()), line.chars().count());
+        if line_cells.len() % size != 0 {
+            return Err(line_end);
+        }
+        last_position = (line_end.line, line_end.column);
+        if completed_in_line {
+            // The next puzzle may have another size
+            puzzle_size = None;
+        }
+    }
+
+    if !cells.is_empty() || boards.is_empty() {
+        return Err(ParseError {
+            kind: ParseErrorKind::IncompletePuzzle(cells.len()),
+            line: last_position.0,
+            column: last_position.1
+        });
+    }
+    Ok(boards)
+}
+
+/// Writes the board as one character per cell, `.` for empty cells
+pub fn board_to_line(board: &SudokuBoard) -> String {
+    board.iter().flatten()
+        .map(|cell| cell.map_or('.', value_symbol))
+        .collect()
+}
+
+/// Reads the region map of a Jigsaw board, e.g. exported with `region_map_to_text`:
+/// one character per cell naming its region, `1`-`9` then `A`-`P`. Whitespace is skipped,
+/// so the map can be written on one line or one row per line. <br>
+/// Returns `regions[y][x]` counted from 0, ready for `Layout::jigsaw` or `Layout::with_regions`.
+pub fn parse_region_map(text: &str) -> Result<Vec<Vec<usize>>, RegionError> {
+    let mut regions = Vec::new();
+    let mut last_position = (1, 1);
+    for (line_index, line) in text.lines().enumerate() {
+        for (column_index, c) in line.chars().enumerate() {
+            if c.is_whitespace() {
+                continue;
+            }
+            let region = parse_value(c).ok_or(ParseError {
+                kind: ParseErrorKind::InvalidCharacter(c),
+                line: line_index + 1,
+                column: column_index + 1,
+            })?;
+            regions.push(usize::from(region.get()) - 1);
+        }
+        last_position = (line_index + 1, line.chars().count() + 1);
+    }
+
+    let size = (1..=MAX_SIZE).find(|side| side * side == regions.len()).filter(|&side| Shape::for_size(side).is_some());
+    let Some(size) = size else {
+        return Err(ParseError {
+            kind: ParseErrorKind::UnknownSize(regions.len()),
+            line: last_position.0,
+            column: last_position.1,
+        }.into());
+    };
+    Ok(regions.chunks(size).map(<[usize]>::to_vec).collect())
+}
+
+/// Writes the region map of a Jigsaw board, one row per line, see `parse_region_map`
+pub fn region_map_to_text(regions: &[Vec<usize>]) -> String {
+    regions.iter()
+        .map(|row| row.iter().map(|&region| value_symbol(NonZeroU8::new(region as u8 + 1).unwrap())).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str = "...86..3.8..9....2....71.9..2.....761.....2..5....8...3..7.45.............72..94.";
+
+    /// A full 16x16 grid, shifting every row so the rows, columns and boxes hold every value once
+    fn letters_grid() -> SudokuBoard {
+        (0..16).map(|y| (0..16).map(|x| NonZeroU8::new(((y * 4 + y / 4 + x) % 16 + 1) as u8)).collect()).collect()
+    }
+
+    #[test]
+    fn line_round_trip() {
+        let board = parse_boards(PUZZLE, 1).unwrap().remove(0);
+        assert_eq!(board.len(), 9);
+        assert_eq!(board[0][3], NonZeroU8::new(8));
+        assert_eq!(board_to_line(&board), PUZZLE);
+    }
+
+    #[test]
+    fn line_round_trip_with_letters() {
+        let board = letters_grid();
+        let line = board_to_line(&board);
+        assert_eq!(line.len(), 256);
+        assert!(line.starts_with("123456789ABCDEFG"));
+        assert_eq!(parse_boards(&line, 1).unwrap(), [board]);
+    }
+
+    #[test]
+    fn rows_of_a_16x16_puzzle_starting_like_a_4x4_one() {
+        let mut board = letters_grid();
+        board[0] = vec![None; 16];
+        board[1].iter_mut().filter(|cell| cell.is_some_and(|value| value.get() > 4)).for_each(|cell| *cell = None);
+        let rows: String = board.iter().map(|row| format!("{}\n", board_to_line(&vec![row.clone()]))).collect();
+        assert_eq!(parse_boards(&rows, 1).unwrap(), [board]);
+
+        // Lines of 16 cells that only hold values up to 4 are still read as 4x4 puzzles
+        let text = "1234341221434321\n................\n";
+        assert_eq!(parse_boards(text, usize::MAX).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn zeros_and_decorations_are_read_as_the_plain_line() {
+        let decorated: String = PUZZLE.as_bytes().chunks(9)
+            .map(|row| {
+                let row = std::str::from_utf8(row).unwrap().replace('.', "0");
+                format!("{} | {} | {}\n", &row[..3], &row[3..6], &row[6..])
+            })
+            .collect();
+        assert_eq!(parse_boards(&decorated, 1).unwrap(), parse_boards(PUZZLE, 1).unwrap());
+    }
+
+    #[test]
+    fn several_puzzles() {
+        let text = format!("{PUZZLE}\n\n{}\n", PUZZLE.replace('.', "0"));
+        assert_eq!(parse_boards(&text, usize::MAX).unwrap().len(), 2);
+        assert_eq!(parse_boards(&text, 1).unwrap_err().kind, ParseErrorKind::TooManyCells);
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_character() {
+        let error = parse_boards("..x", 1).unwrap_err();
+        assert_eq!(error, ParseError { kind: ParseErrorKind::InvalidCharacter('x'), line: 1, column: 3 });
+        assert_eq!(parse_boards(&PUZZLE[..80], 1).unwrap_err().kind, ParseErrorKind::UnknownSize(80));
+    }
+
+    #[test]
+    fn region_map_round_trip() {
+        let text = "1122\n1122\n3344\n3344";
+        let regions = parse_region_map(text).unwrap();
+        assert_eq!(regions[2], [2, 2, 3, 3]);
+        assert_eq!(region_map_to_text(&regions), text);
+        assert_eq!(parse_region_map("11223344").unwrap_err(), RegionError::Parse(ParseError {
+            kind: ParseErrorKind::UnknownSize(8),
+            line: 1,
+            column: 9,
+        }));
+    }
+}
@@ -0,0 +1,336 @@
+use std::num::NonZeroU8;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use super::cell::value_to_mask;
+use super::layout::Layout;
+use super::solve_step::SolveStep;
+use super::sudoku::SudokuBoard;
+use super::sudoku_errors::ErrorNoSolution;
+use super::techniques;
+
+/// Named deductions, ordered from the easiest to the hardest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Technique {
+    /// A value has only one possible cell in a row, column or box
+    HiddenSingle,
+    /// A cell has only one candidate left
+    NakedSingle,
+    /// A value confined to one line within a box is removed from the rest of that line
+    Pointing,
+    /// A value confined to one box within a line is removed from the rest of that box
+    Claiming,
+    /// Two cells of a unit share the same two candidates
+    NakedPair,
+    /// A value confined to the same two columns in two rows, or the other way around
+    XWing,
+    /// Two values of a unit fit only in the same two cells
+    HiddenPair,
+    /// Three cells of a unit hold only three candidates between them
+    NakedTriple,
+    /// The three-line version of the X-Wing
+    Swordfish,
+    /// Three values of a unit fit only in the same three cells
+    HiddenTriple,
+    /// A bivalue pivot with two bivalue pincers
+    XYWing,
+    /// A trivalue pivot with two bivalue pincers
+    XYZWing,
+    /// Avoids a deadly pattern that would give the puzzle two solutions
+    UniqueRectangle,
+    /// Four cells of a unit hold only four candidates between them
+    NakedQuad,
+    /// The four-line version of the X-Wing
+    Jellyfish,
+    /// Four values of a unit fit only in the same four cells
+    HiddenQuad,
+    /// Colouring the conjugate pairs of a single value
+    SimpleColouring,
+}
+
+impl Technique {
+    /// Every technique in the order the logical solver tries them
+    pub const ALL: [Technique; 17] = [
+        Technique::HiddenSingle,
+        Technique::NakedSingle,
+        Technique::Pointing,
+        Technique::Claiming,
+        Technique::NakedPair,
+        Technique::XWing,
+        Technique::HiddenPair,
+        Technique::NakedTriple,
+        Technique::Swordfish,
+        Technique::HiddenTriple,
+        Technique::XYWing,
+        Technique::XYZWing,
+        Technique::UniqueRectangle,
+        Technique::NakedQuad,
+        Technique::Jellyfish,
+        Technique::HiddenQuad,
+        Technique::SimpleColouring,
+    ];
+
+    /// Human-readable name, e.g. "X-Wing"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Technique::HiddenSingle => "Hidden Single",
+            Technique::NakedSingle => "Naked Single",
+            Technique::Pointing => "Pointing",
+            Technique::Claiming => "Claiming",
+            Technique::NakedPair => "Naked Pair",
+            Technique::XWing => "X-Wing",
+            Technique::HiddenPair => "Hidden Pair",
+            Technique::NakedTriple => "Naked Triple",
+            Technique::Swordfish => "Swordfish",
+            Technique::HiddenTriple => "Hidden Triple",
+            Technique::XYWing => "XY-Wing",
+            Technique::XYZWing => "XYZ-Wing",
+            Technique::UniqueRectangle => "Unique Rectangle",
+            Technique::NakedQuad => "Naked Quad",
+            Technique::Jellyfish => "Jellyfish",
+            Technique::HiddenQuad => "Hidden Quad",
+            Technique::SimpleColouring => "Simple Colouring",
+        }
+    }
+
+    fn find(&self, grid: &Grid) -> Option<Deduction> {
+        match self {
+            Technique::HiddenSingle => techniques::hidden_single(grid),
+            Technique::NakedSingle => techniques::naked_single(grid),
+            Technique::Pointing => techniques::pointing(grid),
+            Technique::Claiming => techniques::claiming(grid),
+            Technique::NakedPair => techniques::naked_subset(grid, 2, *self),
+            Technique::NakedTriple => techniques::naked_subset(grid, 3, *self),
+            Technique::NakedQuad => techniques::naked_subset(grid, 4, *self),
+            Technique::HiddenPair => techniques::hidden_subset(grid, 2, *self),
+            Technique::HiddenTriple => techniques::hidden_subset(grid, 3, *self),
+            Technique::HiddenQuad => techniques::hidden_subset(grid, 4, *self),
+            Technique::XWing => techniques::fish(grid, 2, *self),
+            Technique::Swordfish => techniques::fish(grid, 3, *self),
+            Technique::Jellyfish => techniques::fish(grid, 4, *self),
+            Technique::XYWing => techniques::xy_wing(grid),
+            Technique::XYZWing => techniques::xyz_wing(grid),
+            Technique::UniqueRectangle => techniques::unique_rectangle(grid),
+            Technique::SimpleColouring => techniques::simple_colouring(grid),
+        }
+    }
+}
+
+impl std::fmt::Display for Technique {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A single application of a technique. Cells are indexed by `y * size + x`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deduction {
+    pub technique: Technique,
+    /// The cell and the value placed in it, if the deduction places a value
+    pub placement: Option<(usize, u8)>,
+    /// Candidates removed by the deduction as (cell, value) pairs
+    pub eliminations: Vec<(usize, u8)>,
+    /// Cells forming the pattern that justifies the deduction
+    pub pattern: Vec<usize>,
+}
+
+impl Deduction {
+    pub fn placement(technique: Technique, cell: usize, value: u8, pattern: Vec<usize>) -> Self {
+        Deduction {
+            technique,
+            placement: Some((cell, value)),
+            eliminations: Vec::new(),
+            pattern
+        }
+    }
+
+    /// Returns `None` if nothing would be eliminated, so techniques can return it directly
+    pub fn eliminations(technique: Technique, eliminations: Vec<(usize, u8)>, pattern: Vec<usize>) -> Option<Self> {
+        if eliminations.is_empty() {
+            return None;
+        }
+        Some(Deduction {
+            technique,
+            placement: None,
+            eliminations,
+            pattern
+        })
+    }
+}
+
+/// Values and pencil marks of the board as seen by the logical solver
+#[derive(Debug, Clone)]
+pub struct Grid {
+    pub layout: Arc<Layout>,
+    /// 0 for empty cells
+    pub values: Vec<u8>,
+    /// Bit `n - 1` is set if the value `n` is still possible, 0 for filled cells
+    pub candidates: Vec<u32>,
+}
+
+impl Grid {
+    fn new(layout: Arc<Layout>) -> Self {
+        Grid {
+            values: vec![0; layout.cell_count()],
+            candidates: vec![layout.all_values(); layout.cell_count()],
+            layout
+        }
+    }
+
+    /// Every value a cell can hold
+    pub fn value_range(&self) -> RangeInclusive<u8> {
+        1..=self.layout.size() as u8
+    }
+
+    pub fn has_candidate(&self, cell: usize, value: u8) -> bool {
+        self.candidates[cell] & value_to_mask(value) != 0
+    }
+
+    /// Cells of the unit that can still hold the value
+    pub fn cells_with_candidate(&self, unit: &[usize], value: u8) -> Vec<usize> {
+        unit.iter().copied().filter(|&cell| self.has_candidate(cell, value)).collect()
+    }
+
+    fn place(&mut self, cell: usize, value: u8) -> Result<(), ()> {
+        if !self.has_candidate(cell, value) {
+            return Err(());
+        }
+        self.values[cell] = value;
+        self.candidates[cell] = 0;
+        for &peer in self.layout.peers(cell) {
+            self.candidates[peer] &= !value_to_mask(value);
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self, deduction: &Deduction) -> Result<(), ()> {
+        if let Some((cell, value)) = deduction.placement {
+            self.place(cell, value)?;
+        }
+        for &(cell, value) in &deduction.eliminations {
+            self.candidates[cell] &= !value_to_mask(value);
+        }
+        Ok(())
+    }
+
+    fn is_solved(&self) -> bool {
+        self.values.iter().all(|&v| v != 0)
+    }
+
+    /// An empty cell with no candidates, or a value with no place left in some unit
+    fn has_contradiction(&self) -> bool {
+        let empty_cell_without_candidates = (0..self.layout.cell_count()).any(|cell| self.values[cell] == 0 && self.candidates[cell] == 0);
+        let value_without_place = self.layout.units().iter().any(|unit| {
+            let placed_or_possible = unit.iter().fold(0, |mask, &cell| {
+                let value_mask = if self.values[cell] != 0 { value_to_mask(self.values[cell]) } else { 0 };
+                mask | value_mask | self.candidates[cell]
+            });
+            placed_or_possible != self.layout.all_values()
+        });
+        empty_cell_without_candidates || value_without_place
+    }
+}
+
+/// How a logical solve ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogicalOutcome {
+    /// Every cell was filled in
+    Solved,
+    /// None of the known techniques applies, solving further would need guessing
+    Stuck,
+}
+
+/// Solves the sudoku the way a person would: by repeatedly applying the easiest
+/// technique that makes progress, without ever guessing. <br>
+/// Unique rectangles assume the puzzle has exactly one solution.
+#[derive(Debug, Clone)]
+pub struct LogicalSolver {
+    grid: Grid,
+    deductions: Vec<Deduction>,
+}
+
+impl LogicalSolver {
+    /// Creates a solver for the given board, its shape is worked out from the number of rows. <br>
+    /// Fails if the givens already break a rule or leave a cell without candidates.
+    /// Panics if no shape has that many rows, see `Shape::for_size`.
+    pub fn new(starting_state: &SudokuBoard) -> Result<Self, ErrorNoSolution> {
+        Self::with_layout(Layout::for_size(starting_state.len()), starting_state)
+    }
+
+    /// Same as `new`, for a board of the given layout
+    pub fn with_layout(layout: Arc<Layout>, starting_state: &SudokuBoard) -> Result<Self, ErrorNoSolution> {
+        let size = layout.size();
+        let mut grid = Grid::new(layout);
+        for (y, row) in starting_state.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if let Some(value) = cell {
+                    grid.place(y * size + x, u8::from(*value)).map_err(|_| ErrorNoSolution::default())?;
+                }
+            }
+        }
+        if grid.has_contradiction() {
+            return Err(ErrorNoSolution::default());
+        }
+
+        Ok(LogicalSolver {
+            grid,
+            deductions: Vec::new()
+        })
+    }
+
+    /// Finds the easiest deduction available on the current board without applying it
+    fn next_deduction(&self) -> Option<Deduction> {
+        if self.grid.is_solved() || self.grid.has_contradiction() {
+            return None;
+        }
+        Technique::ALL.iter().find_map(|technique| technique.find(&self.grid))
+    }
+
+    /// Finds the easiest step available on the current board without applying it
+    pub fn next_step(&self) -> Option<SolveStep> {
+        self.next_deduction().map(|deduction| SolveStep::from_deduction(&deduction, &self.grid.layout))
+    }
+
+    /// Applies the easiest available deduction and returns the technique used. <br>
+    /// Returns `None` if the sudoku is solved or no technique applies.
+    pub fn step(&mut self) -> Option<Technique> {
+        let deduction = self.next_deduction()?;
+        self.grid.apply(&deduction).ok()?;
+        let technique = deduction.technique;
+        self.deductions.push(deduction);
+        Some(technique)
+    }
+
+    /// Applies deductions until the sudoku is solved or no technique applies
+    pub fn solve(&mut self) -> LogicalOutcome {
+        while self.step().is_some() {}
+
+        if self.grid.is_solved() {
+            LogicalOutcome::Solved
+        } else {
+            LogicalOutcome::Stuck
+        }
+    }
+
+    /// Every step applied so far, in order
+    pub fn steps(&self) -> Vec<SolveStep> {
+        self.deductions.iter().map(|deduction| SolveStep::from_deduction(deduction, &self.grid.layout)).collect()
+    }
+
+    /// The technique used by every deduction applied so far, in order
+    pub fn techniques_used(&self) -> Vec<Technique> {
+        self.deductions.iter().map(|d| d.technique).collect()
+    }
+
+    /// The board with every value placed so far
+    pub fn board(&self) -> SudokuBoard {
+        self.grid.values.chunks(self.grid.layout.size())
+            .map(|row| row.iter().map(|&value| NonZeroU8::new(value)).collect())
+            .collect()
+    }
+
+    /// Possible values of the cell as a mask, 0 if the cell is filled
+    pub fn candidates(&self, x: usize, y: usize) -> u32 {
+        self.grid.candidates[y * self.grid.layout.size() + x]
+    }
+}
@@ -0,0 +1,22 @@
+/// Information about a puzzle kept by puzzle files, every field is optional
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PuzzleMetadata {
+    /// Who made the puzzle
+    pub author: Option<String>,
+    /// Short description or title
+    pub description: Option<String>,
+    /// Where the puzzle comes from, e.g. a newspaper or a website
+    pub source: Option<String>,
+    /// Difficulty as given by the source, e.g. "Hard"
+    pub difficulty: Option<String>,
+    /// Free-form comment
+    pub comment: Option<String>,
+}
+
+impl PuzzleMetadata {
+    /// Checks if none of the fields are set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
@@ -1,12 +1,19 @@
+//! Board coordinates
+
 use std::ops::*;
 
+/// A position on the board, `x` is the column and `y` the row
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point<T> {
+     /// Column
      pub x: T,
+     /// Row
      pub y: T
 }
 
 impl<T> Point<T> {
+    /// Creates a point from a column and a row
     pub fn new(x: T, y: T) -> Self {
         Point {
             x,
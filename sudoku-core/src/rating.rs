@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::layout::Layout;
+use super::logical_solver::{LogicalOutcome, LogicalSolver, Technique};
+use super::sudoku::SudokuBoard;
+use super::sudoku_errors::RateError;
+
+/// Score given to puzzles the logical solver can't finish.
+/// Sudoku Explainer rates the forcing chains needed for them from around 7.0 up.
+const STUCK_SCORE: f32 = 7.0;
+
+/// Highest bonus the number of technique uses can add on top of the hardest technique
+const MAX_FREQUENCY_BONUS: f32 = 0.5;
+
+/// Difficulty grade derived from a rating score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Grade {
+    /// Singles only, score up to 2.3
+    Easy,
+    /// Score below 3.5
+    Medium,
+    /// Score below 4.5
+    Hard,
+    /// Score below 7.0
+    Expert,
+    /// Score of 7.0 and up, including grids the logical solver can't finish
+    Extreme,
+}
+
+impl Grade {
+    /// Every grade from the easiest to the hardest
+    pub const ALL: [Grade; 5] = [Grade::Easy, Grade::Medium, Grade::Hard, Grade::Expert, Grade::Extreme];
+
+    /// Human-readable name, e.g. "Medium"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Grade::Easy => "Easy",
+            Grade::Medium => "Medium",
+            Grade::Hard => "Hard",
+            Grade::Expert => "Expert",
+            Grade::Extreme => "Extreme",
+        }
+    }
+
+    fn from_score(score: f32) -> Self {
+        match score {
+            s if s <= 2.3 => Grade::Easy,
+            s if s < 3.5 => Grade::Medium,
+            s if s < 4.5 => Grade::Hard,
+            s if s < STUCK_SCORE => Grade::Expert,
+            _ => Grade::Extreme,
+        }
+    }
+}
+
+impl std::fmt::Display for Grade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl Technique {
+    /// Difficulty of the technique on the Sudoku Explainer scale
+    pub fn score(&self) -> f32 {
+        match self {
+            Technique::HiddenSingle => 1.5,
+            Technique::NakedSingle => 2.3,
+            Technique::Pointing => 2.6,
+            Technique::Claiming => 2.8,
+            Technique::NakedPair => 3.0,
+            Technique::XWing => 3.2,
+            Technique::HiddenPair => 3.4,
+            Technique::NakedTriple => 3.6,
+            Technique::Swordfish => 3.8,
+            Technique::HiddenTriple => 4.0,
+            Technique::XYWing => 4.2,
+            Technique::XYZWing => 4.4,
+            Technique::UniqueRectangle => 4.5,
+            Technique::NakedQuad => 5.0,
+            Technique::Jellyfish => 5.2,
+            Technique::HiddenQuad => 5.4,
+            // Rated like the shortest X-Chains, which it is a special case of
+            Technique::SimpleColouring => 6.6,
+        }
+    }
+
+    fn is_single(&self) -> bool {
+        matches!(self, Technique::HiddenSingle | Technique::NakedSingle)
+    }
+}
+
+/// How hard a grid is for a person to solve
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rating {
+    /// Grade matching the score
+    pub grade: Grade,
+    /// Score of the hardest technique needed, plus up to 0.5 for every
+    /// use of a technique harder than a single (each use adds a hundredth of its score). <br>
+    /// Grids the logical solver can't finish get at least 7.0.
+    pub score: f32,
+    /// Hardest technique the logical solve needed, `None` if nothing had to be deduced
+    pub hardest_technique: Option<Technique>,
+    /// How many times each technique was used
+    pub technique_counts: BTreeMap<Technique, usize>,
+    /// False if solving the grid needs more than the known techniques
+    pub solved_logically: bool,
+}
+
+impl Rating {
+    /// Rates any grid by solving it logically, its shape is worked out from the number of rows. <br>
+    /// Unique rectangles assume a single solution, so grids with several solutions may be rated too low.
+    /// The scores are calibrated on 9x9 grids, other sizes only get a rough idea.
+    pub fn of(board: &SudokuBoard) -> Result<Self, RateError> {
+        Self::with_layout(Layout::for_size(board.len()), board)
+    }
+
+    /// Same as `of`, for a grid of the given layout. Killer Sudoku isn't rated, see `RateError::Unrated`.
+    pub fn with_layout(layout: Arc<Layout>, board: &SudokuBoard) -> Result<Self, RateError> {
+        if !layout.cages().is_empty() {
+            return Err(RateError::Unrated);
+        }
+        let mut solver = LogicalSolver::with_layout(layout, board)?;
+        let outcome = solver.solve();
+
+        let mut technique_counts = BTreeMap::new();
+        for technique in solver.techniques_used() {
+            *technique_counts.entry(technique).or_insert(0) += 1;
+        }
+        let hardest_technique = technique_counts.keys().max_by(|a, b| a.score().total_cmp(&b.score())).copied();
+
+        let frequency_bonus: f32 = technique_counts.iter()
+            .filter(|(technique, _)| !technique.is_single())
+            .map(|(technique, &count)| technique.score() * count as f32 / 100.0)
+            .sum();
+        let mut score = hardest_technique.map_or(0.0, |t| t.score()) + frequency_bonus.min(MAX_FREQUENCY_BONUS);
+        let solved_logically = outcome == LogicalOutcome::Solved;
+        if !solved_logically {
+            score = score.max(STUCK_SCORE);
+        }
+        // Keep one decimal place, like Sudoku Explainer does
+        let score = (score * 10.0).round() / 10.0;
+
+        Ok(Rating {
+            grade: Grade::from_score(score),
+            score,
+            hardest_technique,
+            technique_counts,
+            solved_logically
+        })
+    }
+}
+
+impl std::fmt::Display for Rating {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({:.1})", self.grade, self.score)
+    }
+}
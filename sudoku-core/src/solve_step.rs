@@ -0,0 +1,110 @@
+use std::num::NonZeroU8;
+
+use super::layout::Layout;
+use super::logical_solver::{Deduction, LogicalOutcome, Technique};
+use super::point::Point;
+
+/// What a solving step changes on the board
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepAction {
+    /// The value is placed in the step's cell
+    Place(NonZeroU8),
+    /// Candidates removed by the step, as (cell, value) pairs
+    Eliminate(Vec<(Point<usize>, NonZeroU8)>),
+}
+
+/// One deduction of a logical solve, in a form that can be replayed or explained
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolveStep {
+    /// Technique that justifies the step
+    pub technique: Technique,
+    /// The cell the step is about: where the value is placed, or the first cell losing a candidate
+    pub cell: Point<usize>,
+    /// Value placed or candidates removed by the step
+    pub action: StepAction,
+    /// Cells that justify the step
+    pub reasons: Vec<Point<usize>>,
+}
+
+impl SolveStep {
+    /// The value placed by the step, `None` for eliminations
+    pub fn placed_value(&self) -> Option<NonZeroU8> {
+        match self.action {
+            StepAction::Place(value) => Some(value),
+            StepAction::Eliminate(_) => None,
+        }
+    }
+
+    /// Candidates removed by the step, empty for placements
+    pub fn removed_candidates(&self) -> &[(Point<usize>, NonZeroU8)] {
+        match &self.action {
+            StepAction::Place(_) => &[],
+            StepAction::Eliminate(removed) => removed,
+        }
+    }
+}
+
+impl SolveStep {
+    /// Converts a deduction of the logical solver, turning its cell indices into coordinates on the layout
+    pub(crate) fn from_deduction(deduction: &Deduction, layout: &Layout) -> Self {
+        let cell_to_point = |cell: usize| Point::new(layout.column_of(cell), layout.row_of(cell));
+        let reasons = deduction.pattern.iter().copied().map(cell_to_point).collect();
+        match deduction.placement {
+            Some((cell, value)) => SolveStep {
+                technique: deduction.technique,
+                cell: cell_to_point(cell),
+                action: StepAction::Place(NonZeroU8::new(value).unwrap()),
+                reasons
+            },
+            None => SolveStep {
+                technique: deduction.technique,
+                cell: cell_to_point(deduction.eliminations[0].0),
+                action: StepAction::Eliminate(deduction.eliminations.iter()
+                    .map(|&(cell, value)| (cell_to_point(cell), NonZeroU8::new(value).unwrap()))
+                    .collect()),
+                reasons
+            },
+        }
+    }
+}
+
+/// The cell as players name it, row and column counted from 1, e.g. `r1c1` for the top left cell
+fn cell_name(cell: Point<usize>) -> String {
+    format!("r{}c{}", cell.y + 1, cell.x + 1)
+}
+
+impl std::fmt::Display for SolveStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.technique)?;
+        match &self.action {
+            StepAction::Place(value) => write!(f, "place {} at {}", value, cell_name(self.cell)),
+            StepAction::Eliminate(removed) => {
+                // Group the removed candidates by value: "remove 3 from r1c2, r1c5; remove 7 from r4c4"
+                let mut values: Vec<NonZeroU8> = removed.iter().map(|&(_, value)| value).collect();
+                values.sort();
+                values.dedup();
+                for (i, value) in values.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "remove {} from ", value)?;
+                    let cells: Vec<String> = removed.iter()
+                        .filter(|(_, v)| v == value)
+                        .map(|&(cell, _)| cell_name(cell))
+                        .collect();
+                    write!(f, "{}", cells.join(", "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Every step of a logical solve and whether it reached the end
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolutionSteps {
+    /// Steps in the order they were applied
+    pub steps: Vec<SolveStep>,
+    /// Whether the steps solve the sudoku or the solver got stuck
+    pub outcome: LogicalOutcome,
+}
@@ -0,0 +1,53 @@
+use super::point::Point;
+
+/// Something the backtracking solver did, in the order it happened. <br>
+/// Replaying the events on top of `SudokuSolver::cells` gives every state the search went through,
+/// as long as a copy of the board is kept at every guess to go back to on a backtrack.
+#[derive(Debug, Clone, Copy)]
+pub enum SolverEvent {
+    /// The cell was set to the value. `guess` is true if other values were possible too,
+    /// the solver then saved a snapshot to come back to if the guess turns out wrong.
+    Collapse {
+        /// Cell that got the value
+        cell: Point<usize>,
+        /// Value placed in the cell
+        value: u8,
+        /// Whether other values were still possible
+        guess: bool,
+    },
+    /// The value was removed from the possible values of an empty cell, because a peer now holds it
+    RemoveCandidate {
+        /// Cell that lost a possible value
+        cell: Point<usize>,
+        /// Value that is no longer possible
+        value: u8,
+    },
+    /// The cell was left without any possible value, so the last guess was wrong
+    Contradiction {
+        /// Cell without possible values
+        cell: Point<usize>,
+    },
+    /// The solver went back to the board as it was before its latest guess,
+    /// where the guessed value is no longer possible in the guessed cell
+    Backtrack {
+        /// Cell of the guess that turned out wrong
+        cell: Point<usize>,
+        /// Value that was guessed
+        value: u8,
+        /// Number of snapshots still saved, the guesses left to revisit
+        depth: usize,
+    },
+}
+
+/// Receives the events of a `SudokuSolver` as they happen, see `SudokuSolver::solve_observed`. <br>
+/// Any `FnMut(&SolverEvent)` closure is an observer.
+pub trait SolverObserver {
+    /// Called for every event, in the order they happen
+    fn on_event(&mut self, event: &SolverEvent);
+}
+
+impl<F: FnMut(&SolverEvent)> SolverObserver for F {
+    fn on_event(&mut self, event: &SolverEvent) {
+        self(event)
+    }
+}
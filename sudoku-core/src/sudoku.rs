@@ -0,0 +1,695 @@
+use rand::Rng;
+use std::num::NonZeroU8;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::budget::{Budget, Meter};
+use super::cell::value_to_mask;
+use super::diagnostics;
+use super::generator::{PuzzleGenerator, PuzzleTarget};
+use super::history::{CellChange, Edit, EditKind, History};
+use super::file_formats;
+use super::layout::{Layout, Shape};
+use super::line_format;
+use super::metadata::PuzzleMetadata;
+use super::logical_solver::LogicalSolver;
+use super::point::Point;
+use super::rating::{Grade, Rating};
+use super::solve_step::{SolutionSteps, SolveStep};
+pub use super::sudoku_errors::{ErrorNoSolution, GenerateError, Interrupted, ParseError, ParseErrorKind, PuzzleError, RateError, RegionError, SolveError};
+use super::sudoku_solver::SudokuSolver;
+
+/// Values of a board indexed as `board[y][x]`, `None` for empty cells. <br>
+/// A board has as many rows as columns, 9 for the classic sudoku.
+pub type SudokuBoard = Vec<Vec<Option<NonZeroU8>>>;
+
+/// Guesses spent looking for the entries to blame when no budget is given, see `Sudoku::explain_no_solution`. <br>
+/// Finding them takes a search per entry, which on an open 16x16 or bigger board can run for minutes.
+const MAX_DIAGNOSIS_ITERATIONS: u64 = 1_000_000;
+
+/// Guesses spent checking for a solution when no technique gives a hint, see `Sudoku::hint`
+const MAX_HINT_CHECK_ITERATIONS: u64 = 100_000;
+
+/// A game of sudoku: the givens, the player's entries and pencil marks, the time played and the undo history
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sudoku {
+    /// Boxes of the board, games saved before other sizes existed are classic 9x9 ones
+    #[cfg_attr(feature = "serde", serde(default))]
+    layout: Arc<Layout>,
+    board: SudokuBoard,
+    read_only: Vec<Vec<bool>>,
+    /// Pencil marks, bit `n - 1` is set if the player noted the value `n`
+    notes: Vec<Vec<u32>>,
+    history: History,
+    metadata: PuzzleMetadata,
+    /// Time spent playing, kept up to date by the frontend
+    elapsed_time: Duration
+}
+
+impl Sudoku {
+    /// Creates a classic 9x9 board without any values, where every cell can be edited
+    pub fn new_empty() -> Self {
+        Self::with_shape(Shape::CLASSIC)
+    }
+
+    /// Creates a board of the shape without any values, where every cell can be edited
+    pub fn with_shape(shape: Shape) -> Self {
+        Self::with_layout(Layout::shared(shape))
+    }
+
+    /// Creates a board of the layout without any values, e.g. one with extra constraints
+    pub fn with_layout(layout: Arc<Layout>) -> Self {
+        let size = layout.size();
+        Self::from_givens_in(layout, vec![vec![None; size]; size])
+    }
+
+    /// Creates a new classic Sudoku puzzle with the given difficulty.
+    /// The puzzle always has exactly one solution. Use a `PuzzleGenerator` for other sizes.
+    /// # Arguments
+    /// * `difficulty` - a number between 1 and 81 (inclusive) that represents the number of empty cells in the puzzle
+    /// # Returns
+    /// * a new Sudoku puzzle with the given difficulty <br>
+    ///   If no more cells can be emptied without making the solution ambiguous,
+    ///   the puzzle will have fewer empty cells than requested
+    pub fn new_puzzle(difficulty: usize) -> Self {
+        Self::new_puzzle_with_rng(difficulty, &mut rand::rng())
+    }
+
+    /// Same as `new_puzzle`, but takes its randomness from `rng`,
+    /// so a seeded generator always gives the same puzzle
+    pub fn new_puzzle_with_rng<R: Rng + ?Sized>(difficulty: usize, rng: &mut R) -> Self {
+        PuzzleGenerator::new(PuzzleTarget::EmptyCells(difficulty), rng).generate().expect("a classic grid can always be filled")
+    }
+
+    /// Creates a new Sudoku puzzle of the given grade. The puzzle always has exactly one solution. <br>
+    /// If no puzzle of that grade turns up after a number of attempts,
+    /// the hardest one found below it is returned.
+    pub fn new_graded_puzzle(grade: Grade) -> Self {
+        Self::new_graded_puzzle_with_rng(grade, &mut rand::rng())
+    }
+
+    /// Same as `new_graded_puzzle`, but takes its randomness from `rng`
+    pub fn new_graded_puzzle_with_rng<R: Rng + ?Sized>(grade: Grade, rng: &mut R) -> Self {
+        PuzzleGenerator::new(PuzzleTarget::Grade(grade), rng).generate().expect("a classic grid can always be filled")
+    }
+
+    /// Creates a game from a puzzle, every filled cell becomes a read-only given. <br>
+    /// The shape of the boxes is worked out from the number of rows, see `Shape::for_size`,
+    /// which panics if there is none.
+    pub fn from_givens(board: SudokuBoard) -> Self {
+        Self::from_givens_in(Layout::for_size(board.len()), board)
+    }
+
+    /// Same as `from_givens`, for a board of the given layout, e.g. a Sudoku-X one. <br>
+    /// Panics if the board doesn't have the size of the layout.
+    pub fn from_givens_in(layout: Arc<Layout>, board: SudokuBoard) -> Self {
+        assert!(board.len() == layout.size() && board.iter().all(|row| row.len() == layout.size()),
+            "the board doesn't have the size of the layout");
+        Sudoku {
+            read_only: Self::infer_read_only(&board),
+            notes: vec![vec![0; layout.size()]; layout.size()],
+            layout,
+            board,
+            history: History::new(),
+            metadata: PuzzleMetadata::default(),
+            elapsed_time: Duration::ZERO
+        }
+    }
+
+    fn infer_read_only(board: &SudokuBoard) -> Vec<Vec<bool>> {
+        board.iter().map(|row| row.iter().map(Option::is_some).collect()).collect()
+    }
+
+    /// Constraints of the board, see `Layout::constraints`
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    /// Shape of the boxes, which also gives the size of the board
+    pub fn shape(&self) -> Shape {
+        self.layout.shape()
+    }
+
+    /// Number of rows, columns and values of the board
+    pub fn size(&self) -> usize {
+        self.layout.size()
+    }
+
+    /// Checks if the board, givens, notes and history all fit the shape,
+    /// e.g. for a game read from a file that may be damaged. <br>
+    /// Every change in the history has to be on the board, with values and notes of the board's size,
+    /// and must not touch a given, so undoing and redoing it is safe.
+    pub fn is_consistent(&self) -> bool {
+        fn is_square<T>(grid: &[Vec<T>], size: usize) -> bool {
+            grid.len() == size && grid.iter().all(|row| row.len() == size)
+        }
+        let size = self.size();
+        let fits = |value: Option<NonZeroU8>| value.is_none_or(|value| usize::from(value.get()) <= size);
+        let notes_fit = |notes: u32| notes & !self.layout.all_values() == 0;
+        is_square(&self.board, size) && is_square(&self.read_only, size) && is_square(&self.notes, size)
+            && self.board.iter().flatten().all(|&value| fits(value))
+            && self.history.edits().flat_map(|edit| &edit.changes).all(|change| {
+                change.x < size && change.y < size && !self.read_only[change.y][change.x]
+                    && fits(change.value_before) && fits(change.value_after)
+                    && notes_fit(change.notes_before) && notes_fit(change.notes_after)
+            })
+    }
+
+    /// Checks if the values on the board make a proper puzzle: one with exactly one solution
+    pub fn validate_puzzle(&self) -> Result<(), PuzzleError> {
+        match self.count_solutions(2) {
+            0 => Err(PuzzleError::NoSolution(self.explain_no_solution())),
+            1 => Ok(()),
+            _ => Err(PuzzleError::MultipleSolutions),
+        }
+    }
+
+    /// Starts the game over with the values on the board as its read-only givens,
+    /// e.g. once a puzzle typed in by the player was checked with `validate_puzzle`. <br>
+    /// Notes, the history and the time played are reset.
+    pub fn lock_givens(&mut self) {
+        self.read_only = Self::infer_read_only(&self.board);
+        self.notes = vec![vec![0; self.size()]; self.size()];
+        self.history = History::new();
+        self.elapsed_time = Duration::ZERO;
+    }
+
+    /// Reads every puzzle in the text, filled cells become read-only givens. <br>
+    /// Puzzles are written in reading order with `.` or `0` for empty cells and `A`-`P` for values above 9,
+    /// either on one line (81 characters for a 9x9 puzzle) or spread over several lines, as long as
+    /// every line holds whole rows. The first line of a puzzle tells its size.
+    /// Whitespace and the `|`, `+` and `-` decorations printed by `Display` are skipped.
+    pub fn parse_many(text: &str) -> Result<Vec<Sudoku>, ParseError> {
+        let boards = line_format::parse_boards(text, usize::MAX)?;
+        Ok(boards.into_iter().map(Sudoku::from_givens).collect())
+    }
+
+    /// Writes the board as one line, one character per cell and `.` for empty cells.
+    /// Both the givens and the player's values are written.
+    pub fn to_line(&self) -> String {
+        line_format::board_to_line(&self.board)
+    }
+
+    /// Reads a SadMan Software `.sdk` file, keeping its author, description, source, level and comment headers
+    pub fn from_sdk(text: &str) -> Result<Sudoku, ParseError> {
+        let (board, metadata) = file_formats::parse_sdk(text)?;
+        let mut sudoku = Sudoku::from_givens(board);
+        sudoku.metadata = metadata;
+        Ok(sudoku)
+    }
+
+    /// Writes the givens and the metadata as a SadMan Software `.sdk` file
+    pub fn to_sdk(&self) -> String {
+        file_formats::write_sdk(&self.givens(), &self.metadata)
+    }
+
+    /// Reads a SadMan Software `.sdm` file, one puzzle per line
+    pub fn parse_sdm(text: &str) -> Result<Vec<Sudoku>, ParseError> {
+        let boards = file_formats::parse_sdm(text)?;
+        Ok(boards.into_iter().map(Sudoku::from_givens).collect())
+    }
+
+    /// Writes the givens of every puzzle as a SadMan Software `.sdm` file. The format has no room for metadata.
+    pub fn to_sdm(puzzles: &[Sudoku]) -> String {
+        let boards: Vec<SudokuBoard> = puzzles.iter().map(Sudoku::givens).collect();
+        file_formats::write_sdm(&boards)
+    }
+
+    /// Reads a Simple Sudoku `.ss` grid
+    pub fn from_ss(text: &str) -> Result<Sudoku, ParseError> {
+        file_formats::parse_ss(text).map(Sudoku::from_givens)
+    }
+
+    /// Writes the givens as a Simple Sudoku `.ss` grid. The format has no room for metadata.
+    pub fn to_ss(&self) -> String {
+        file_formats::write_ss(&self.givens(), self.shape())
+    }
+
+    /// Information about the puzzle read from its file
+    pub fn metadata(&self) -> &PuzzleMetadata {
+        &self.metadata
+    }
+
+    /// Replaces the information written to `.sdk` files
+    pub fn set_metadata(&mut self, metadata: PuzzleMetadata) {
+        self.metadata = metadata;
+    }
+
+    /// Time spent playing this game
+    pub fn elapsed_time(&self) -> Duration {
+        self.elapsed_time
+    }
+
+    /// Adds to the time spent playing, the model doesn't measure time on its own
+    pub fn add_elapsed_time(&mut self, time: Duration) {
+        self.elapsed_time += time;
+    }
+
+    /// The board with only the read-only givens filled in
+    fn givens(&self) -> SudokuBoard {
+        self.board.iter().zip(&self.read_only)
+            .map(|(row, read_only)| row.iter().zip(read_only).map(|(cell, &given)| cell.filter(|_| given)).collect())
+            .collect()
+    }
+
+    /// Value of the cell in column `x` and row `y`
+    pub fn get_cell(&self, x: usize, y: usize) -> Option<NonZeroU8> {
+        self.board[y][x]
+    }
+
+    /// Sets or clears the value of the cell. Read-only cells are left untouched.
+    pub fn set_cell(&mut self, x: usize, y: usize, value: Option<NonZeroU8>) {
+        if !self.read_only[y][x] {
+            self.record(EditKind::Cell, |sudoku| sudoku.board[y][x] = value);
+        }
+    }
+
+    /// Sets the value and removes it from the notes of every cell in the same row, column and region
+    pub fn set_cell_and_clear_notes(&mut self, x: usize, y: usize, value: NonZeroU8) {
+        if self.read_only[y][x] {
+            return;
+        }
+        self.record(EditKind::Cell, |sudoku| {
+            sudoku.board[y][x] = Some(value);
+            let layout = Arc::clone(&sudoku.layout);
+            for &peer in layout.peers(y * layout.size() + x) {
+                sudoku.notes[layout.row_of(peer)][layout.column_of(peer)] &= !value_to_mask(value.get());
+            }
+        });
+    }
+
+    /// Checks if there are no values on the board at all
+    pub fn is_empty(&self) -> bool {
+        self.board.iter().flatten().all(|cell| cell.is_none())
+    }
+
+    /// Checks if the cell is a given of the puzzle
+    pub fn is_read_only(&self, x: usize, y: usize) -> bool {
+        self.read_only[y][x]
+    }
+
+    /// Checks if the player noted `value` in the cell
+    pub fn has_note(&self, x: usize, y: usize, value: NonZeroU8) -> bool {
+        self.notes[y][x] & value_to_mask(value.get()) != 0
+    }
+
+    /// Checks if the cell has any notes
+    pub fn has_notes(&self, x: usize, y: usize) -> bool {
+        self.notes[y][x] != 0
+    }
+
+    /// Adds the note if it isn't there yet, removes it otherwise.
+    /// Notes can only be written into empty cells.
+    pub fn toggle_note(&mut self, x: usize, y: usize, value: NonZeroU8) {
+        if !self.read_only[y][x] && self.board[y][x].is_none() {
+            self.record(EditKind::Note, |sudoku| sudoku.notes[y][x] ^= value_to_mask(value.get()));
+        }
+    }
+
+    /// Removes every note from the cell. Read-only cells are left untouched.
+    pub fn clear_notes(&mut self, x: usize, y: usize) {
+        if !self.read_only[y][x] {
+            self.record(EditKind::Note, |sudoku| sudoku.notes[y][x] = 0);
+        }
+    }
+
+    /// Removes every value and note entered by the player
+    pub fn clear(&mut self) {
+        self.record(EditKind::Clear, |sudoku| {
+            for y in 0..sudoku.size() {
+                for x in 0..sudoku.size() {
+                    if !sudoku.read_only[y][x] {
+                        sudoku.board[y][x] = None;
+                    }
+                    sudoku.notes[y][x] = 0;
+                }
+            }
+        });
+    }
+
+    /// Fills in the rest of the board with a solution. <br>
+    /// If there is none, the board is left as it is and the error explains why.
+    pub fn solve(&mut self) -> Result<(), ErrorNoSolution> {
+        let solved_sudoku= self.solver().and_then(|s| s.solve());
+        match solved_sudoku {
+            Ok(solved_sudoku) => {
+                self.apply_solution(solved_sudoku);
+                Ok(())
+            },
+            Err(_) => Err(self.explain_no_solution())
+        }
+    }
+
+    /// Same as `solve`, but gives up once the budget runs out, leaving the board as it is. <br>
+    /// Explaining why there is no solution spends the same budget, see `explain_no_solution_within`.
+    pub fn solve_within(&mut self, budget: &Budget) -> Result<(), SolveError> {
+        let mut meter = budget.start();
+        let solution = SudokuSolver::with_layout(Arc::clone(&self.layout), &self.board)
+            .map_err(|_| SolveError::NoSolution(ErrorNoSolution::default()))
+            .and_then(|mut solver| solver.solve_metered(&mut meter));
+        match solution {
+            Ok(solution) => {
+                self.apply_solution(solution);
+                Ok(())
+            },
+            Err(SolveError::NoSolution(_)) => Err(SolveError::NoSolution(self.diagnose(&mut meter))),
+            Err(e) => Err(e)
+        }
+    }
+
+    /// Creates a solver for the values on the board, for frontends that solve in slices with `SudokuSolver::solve_within`. <br>
+    /// If the values already break a rule, the error explains why.
+    pub fn solver(&self) -> Result<SudokuSolver, ErrorNoSolution> {
+        SudokuSolver::with_layout(Arc::clone(&self.layout), &self.board).map_err(|_| self.explain_no_solution())
+    }
+
+    /// Fills in the rest of the board with a solution found by the `solver`, as one edit that can be undone
+    pub fn apply_solution(&mut self, solution: SudokuBoard) {
+        self.record(EditKind::Solve, |sudoku| sudoku.board = solution);
+    }
+
+    /// Works out why the current board has no solution: values repeated in a row, column or region,
+    /// empty cells left without a possible value and entries whose removal makes it solvable again. <br>
+    /// Only meaningful if the board really has no solution. The search for the entries to blame is limited,
+    /// on big open boards they may be left out, use `explain_no_solution_within` to pick the budget.
+    pub fn explain_no_solution(&self) -> ErrorNoSolution {
+        self.explain_no_solution_within(&Budget::unlimited().with_iterations(MAX_DIAGNOSIS_ITERATIONS))
+    }
+
+    /// Same as `explain_no_solution`, but the entries to blame are left out once the budget runs out
+    pub fn explain_no_solution_within(&self, budget: &Budget) -> ErrorNoSolution {
+        self.diagnose(&mut budget.start())
+    }
+
+    fn diagnose(&self, meter: &mut Meter) -> ErrorNoSolution {
+        diagnostics::diagnose(&self.layout, &self.board, &self.read_only, meter)
+    }
+
+    /// Reverts the last edit. Returns false if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(edit) = self.history.undo() else { return false };
+        for change in edit.changes {
+            self.board[change.y][change.x] = change.value_before;
+            self.notes[change.y][change.x] = change.notes_before;
+        }
+        true
+    }
+
+    /// Reapplies the last undone edit. Returns false if there was nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(edit) = self.history.redo() else { return false };
+        for change in edit.changes {
+            self.board[change.y][change.x] = change.value_after;
+            self.notes[change.y][change.x] = change.notes_after;
+        }
+        true
+    }
+
+    /// Edits that can be undone or redone
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Runs the edit and saves every cell it changed to the history
+    fn record(&mut self, kind: EditKind, edit: impl FnOnce(&mut Self)) {
+        let (board_before, notes_before) = (self.board.clone(), self.notes.clone());
+        edit(self);
+
+        let mut changes = Vec::new();
+        for y in 0..self.size() {
+            for x in 0..self.size() {
+                if board_before[y][x] != self.board[y][x] || notes_before[y][x] != self.notes[y][x] {
+                    changes.push(CellChange {
+                        x,
+                        y,
+                        value_before: board_before[y][x],
+                        value_after: self.board[y][x],
+                        notes_before: notes_before[y][x],
+                        notes_after: self.notes[y][x]
+                    });
+                }
+            }
+        }
+        self.history.push(Edit { kind, changes });
+    }
+
+    /// Solves a copy of the board the way a person would and returns every step taken. <br>
+    /// The board itself is left untouched. If no known technique applies at some point,
+    /// the steps end there and the outcome is `Stuck`. The techniques don't use the sums of Killer cages,
+    /// so on Killer boards the steps rarely get far.
+    pub fn solution_steps(&self) -> Result<SolutionSteps, ErrorNoSolution> {
+        let mut solver = LogicalSolver::with_layout(Arc::clone(&self.layout), &self.board)?;
+        let outcome = solver.solve();
+        Ok(SolutionSteps {
+            steps: solver.steps(),
+            outcome
+        })
+    }
+
+    /// Finds the next value the player can place, using the easiest techniques available. <br>
+    /// Candidates removed on the way aren't visible on the board, so the cells justifying
+    /// those eliminations are added to the reasons of the returned step. <br>
+    /// Returns `None` if the board is full or no known technique applies. In the latter case the board
+    /// is checked for a solution, on big open boards that check may give up and `None` is returned as well. <br>
+    /// Like `solution_steps` the hint doesn't use the sums of Killer cages.
+    pub fn hint(&self) -> Result<Option<SolveStep>, ErrorNoSolution> {
+        let mut solver = LogicalSolver::with_layout(Arc::clone(&self.layout), &self.board).map_err(|_| self.explain_no_solution())?;
+        let mut elimination_reasons = Vec::new();
+        while let Some(mut step) = solver.next_step() {
+            if step.placed_value().is_some() {
+                for reason in elimination_reasons {
+                    if reason != step.cell && !step.reasons.contains(&reason) {
+                        step.reasons.push(reason);
+                    }
+                }
+                return Ok(Some(step));
+            }
+            elimination_reasons.extend(step.reasons);
+            solver.step();
+        }
+
+        // Being stuck may mean that a wrong entry left the board without a solution
+        let budget = Budget::unlimited().with_iterations(MAX_HINT_CHECK_ITERATIONS);
+        let solutions = SudokuSolver::with_layout(Arc::clone(&self.layout), &self.board).map_or(Ok(0), |s| s.count_solutions_within(1, &budget));
+        if solutions == Ok(0) {
+            return Err(self.explain_no_solution());
+        }
+        Ok(None)
+    }
+
+    /// Rates how hard the current board is to solve for a person, Killer Sudoku isn't rated
+    pub fn rate(&self) -> Result<Rating, RateError> {
+        Rating::with_layout(Arc::clone(&self.layout), &self.board)
+    }
+
+    /// Counts the solutions of the current board, stopping as soon as `limit` of them were found.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        SudokuSolver::with_layout(Arc::clone(&self.layout), &self.board).map_or(0, |s| s.count_solutions(limit))
+    }
+
+    /// Checks if the current board has exactly one solution
+    pub fn has_unique_solution(&self) -> bool {
+        self.count_solutions(2) == 1
+    }
+
+    /// Every pair of filled cells that share a row, column, region or other constraint whose values must differ,
+    /// yet hold the same value.
+    /// Each pair is listed once, with the cell coming first in reading order on the left.
+    pub fn conflicts(&self) -> Vec<(Point<usize>, Point<usize>)> {
+        diagnostics::find_duplicates(&self.layout, &self.board)
+    }
+
+    /// Checks if the cell holds the same value as one of its peers, e.g. another cell in its row, column or region
+    pub fn is_in_conflict(&self, x: usize, y: usize) -> bool {
+        match self.board[y][x] {
+            Some(value) => self.layout.peers(y * self.size() + x).iter()
+                .any(|&peer| self.board[self.layout.row_of(peer)][self.layout.column_of(peer)] == Some(value)),
+            None => false
+        }
+    }
+
+    /// Checks if the board is full and satisfies all the sudoku rules, see `Layout::constraints`. <br>
+    /// Generated puzzles have exactly one solution, so any valid fill is the intended answer.
+    pub fn check(&self) -> bool {
+        self.is_full() && self.layout.check(&self.board)
+    }
+
+    fn is_full(&self) -> bool {
+        self.board.iter().all(|row| row.iter().all(|cell| cell.is_some()))
+    }
+}
+
+/// Reads a single puzzle, see `Sudoku::parse_many` for the accepted format
+impl std::str::FromStr for Sudoku {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let board = line_format::parse_boards(s, 1)?.remove(0);
+        Ok(Sudoku::from_givens(board))
+    }
+}
+
+/// Prints the board as a grid with `|` and `+---` separators between the regions, `.` for empty cells
+/// and the letters `A`-`P` for values above 9
+impl std::fmt::Display for Sudoku {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let shape = self.shape();
+        // Every box takes two characters per cell plus the two of the "| " after it
+        let box_chars = 2 * shape.box_width() + 2;
+        for (y, row) in self.board.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                write!(f, "{} ", cell.map_or('.', line_format::value_symbol))?;
+                if x % shape.box_width() == shape.box_width() - 1 && x != row.len() - 1 {
+                    write!(f, "| ")?;
+                }
+            }
+
+            writeln!(f)?;
+            if y % shape.box_height() == shape.box_height() - 1 && y != self.board.len() - 1 {
+                for x in 0..(2*row.len() + 2 * (row.len() / shape.box_width() - 1) - 1) {
+                    if x % box_chars == box_chars - 2 {
+                        write!(f, "+")?;
+                    } else {
+                        write!(f, "-")?;
+                    }
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cage;
+
+    const PUZZLE: &str = "...86..3.8..9....2....71.9..2.....761.....2..5....8...3..7.45.............72..94.";
+
+    fn value(value: u8) -> NonZeroU8 {
+        NonZeroU8::new(value).unwrap()
+    }
+
+    fn solution() -> Sudoku {
+        let mut solved: Sudoku = PUZZLE.parse().unwrap();
+        solved.solve().unwrap();
+        solved
+    }
+
+    /// The puzzle with a value entered in the first empty cell that fits its peers but not the solution
+    fn with_wrong_entry() -> (Sudoku, Point<usize>) {
+        let mut sudoku: Sudoku = PUZZLE.parse().unwrap();
+        let right = solution().get_cell(0, 0).unwrap();
+        let wrong = (1..=9).map(value)
+            .find(|&wrong| wrong != right && sudoku.layout.peers(0).iter().all(|&peer| sudoku.get_cell(peer % 9, peer / 9) != Some(wrong)))
+            .unwrap();
+        sudoku.set_cell(0, 0, Some(wrong));
+        (sudoku, Point::new(0, 0))
+    }
+
+    #[test]
+    fn solve_fills_in_the_single_solution() {
+        let sudoku: Sudoku = PUZZLE.parse().unwrap();
+        assert!(sudoku.has_unique_solution());
+        assert_eq!(sudoku.validate_puzzle(), Ok(()));
+        let solved = solution();
+        assert!(solved.check());
+        assert_eq!(solved.history().edits().count(), 1);
+    }
+
+    #[test]
+    fn undo_and_redo_restore_values_and_notes() {
+        let mut sudoku: Sudoku = PUZZLE.parse().unwrap();
+        sudoku.toggle_note(0, 0, value(4));
+        sudoku.set_cell_and_clear_notes(1, 0, value(4));
+        assert!(!sudoku.has_note(0, 0, value(4)));
+
+        assert!(sudoku.undo());
+        assert_eq!(sudoku.get_cell(1, 0), None);
+        assert!(sudoku.has_note(0, 0, value(4)));
+        assert!(sudoku.redo());
+        assert_eq!(sudoku.get_cell(1, 0), Some(value(4)));
+        assert!(!sudoku.has_note(0, 0, value(4)));
+    }
+
+    #[test]
+    fn givens_are_read_only() {
+        let mut sudoku: Sudoku = PUZZLE.parse().unwrap();
+        sudoku.set_cell(3, 0, Some(value(1)));
+        sudoku.clear_notes(3, 0);
+        sudoku.clear();
+        assert_eq!(sudoku.get_cell(3, 0), Some(value(8)));
+        assert!(sudoku.history().edits().flat_map(|edit| &edit.changes).all(|change| (change.x, change.y) != (3, 0)));
+    }
+
+    #[test]
+    fn explains_which_entry_breaks_the_puzzle() {
+        let (mut sudoku, entry) = with_wrong_entry();
+        let error = sudoku.solve().unwrap_err();
+        assert_eq!(error.culprits, [entry]);
+    }
+
+    #[test]
+    fn diagnosis_gives_up_with_the_budget() {
+        let (mut sudoku, _) = with_wrong_entry();
+        let error = sudoku.solve_within(&Budget::unlimited().with_iterations(1)).unwrap_err();
+        assert_eq!(error, SolveError::Interrupted(Interrupted::Timeout));
+        assert!(sudoku.explain_no_solution_within(&Budget::unlimited().with_iterations(1)).culprits.is_empty());
+    }
+
+    #[test]
+    fn hint_places_a_value_of_the_solution() {
+        let sudoku: Sudoku = PUZZLE.parse().unwrap();
+        let step = sudoku.hint().unwrap().unwrap();
+        assert_eq!(step.placed_value(), solution().get_cell(step.cell.x, step.cell.y));
+        assert!(step.to_string().ends_with(&format!(" at r{}c{}", step.cell.y + 1, step.cell.x + 1)));
+    }
+
+    #[test]
+    fn killer_cages_are_part_of_the_rules() {
+        let solved = solution().board;
+        let sum = u32::from(solved[0][0].unwrap().get() + solved[0][1].unwrap().get());
+        let killer = |sum, board: &SudokuBoard| {
+            let layout = Layout::new(Shape::CLASSIC).with_cages(vec![Cage::new(vec![0, 1], sum)]);
+            Sudoku::from_givens_in(Arc::new(layout), board.clone())
+        };
+        assert!(killer(sum, &solved).check());
+        assert!(!killer(sum + 1, &solved).check());
+
+        let mut board = solved.clone();
+        board[0][0] = None;
+        board[0][1] = None;
+        assert_eq!(killer(sum, &board).count_solutions(2), 1);
+        assert_eq!(killer(sum + 1, &board).count_solutions(2), 0);
+        assert_eq!(killer(sum, &board).rate(), Err(RateError::Unrated));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let (mut sudoku, _) = with_wrong_entry();
+        sudoku.toggle_note(1, 0, value(5));
+        sudoku.set_metadata(PuzzleMetadata { author: Some(String::from("Jane")), ..PuzzleMetadata::default() });
+        sudoku.add_elapsed_time(Duration::from_secs(90));
+        let json = serde_json::to_string(&sudoku).unwrap();
+        assert_eq!(serde_json::from_str::<Sudoku>(&json).unwrap(), sudoku);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_keeps_the_layout() {
+        let regions = crate::parse_region_map("\
+            112222333\n112222333\n111112333\n\
+            444555666\n444555669\n774585699\n\
+            744588669\n777788699\n778888999").unwrap();
+        let layout = Layout::jigsaw(&regions).unwrap().with_cages(vec![Cage::new(vec![0, 9], 10)]);
+        let sudoku = Sudoku::with_layout(Arc::new(layout));
+        let json = serde_json::to_string(&sudoku).unwrap();
+        let read: Sudoku = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.layout(), sudoku.layout());
+        assert_eq!(read.layout().region_map(), sudoku.layout().region_map());
+        assert_eq!(read.layout().cages(), sudoku.layout().cages());
+    }
+}
@@ -0,0 +1,233 @@
+use super::point::Point;
+
+/// The sudoku can't be solved. <br>
+/// Errors returned by `Sudoku` say why, the solvers themselves leave the diagnostics empty.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ErrorNoSolution {
+    /// Pairs of filled cells holding the same value in a row, column or region
+    pub duplicates: Vec<(Point<usize>, Point<usize>)>,
+    /// Empty cells left without any possible value once the filled cells
+    /// and the values they force are taken into account
+    pub dead_cells: Vec<Point<usize>>,
+    /// Values entered by the player whose removal makes the sudoku solvable again. <br>
+    /// Left empty if the search for them ran out of budget, see `Sudoku::explain_no_solution_within`
+    pub culprits: Vec<Point<usize>>,
+}
+
+impl std::fmt::Display for ErrorNoSolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Sudoku has no valid solution")?;
+        if !self.duplicates.is_empty() {
+            write!(f, ", {} pair(s) of cells repeat a value", self.duplicates.len())?;
+        }
+        if !self.dead_cells.is_empty() {
+            write!(f, ", {} cell(s) have no possible value left", self.dead_cells.len())?;
+        }
+        if !self.culprits.is_empty() {
+            let culprits: Vec<String> = self.culprits.iter().map(|p| p.to_string()).collect();
+            write!(f, ", removing {} makes it solvable", culprits.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ErrorNoSolution {}
+
+/// Text couldn't be read as a sudoku. <br>
+/// `line` and `column` are counted from 1 and point at the character where reading stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// What went wrong
+    pub kind: ParseErrorKind,
+    /// Line of the text where the error was found
+    pub line: usize,
+    /// Column (in characters) of the line where the error was found
+    pub column: usize,
+}
+
+/// The ways reading a sudoku from text can fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A character that is neither a value of the puzzle, an empty cell (`.` or `0`) nor whitespace or a grid decoration
+    InvalidCharacter(char),
+    /// The first line of a puzzle holds this many cells, which is neither a whole puzzle nor whole rows of a known size
+    UnknownSize(usize),
+    /// A line ends in the middle of a row, it holds this many cells
+    IncompleteRow(usize),
+    /// The text ends before the puzzle is complete, it holds this many cells
+    IncompletePuzzle(usize),
+    /// A cell follows the last cell of the puzzle on the same line,
+    /// or a second puzzle follows where only one was expected
+    TooManyCells,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match self.kind {
+            ParseErrorKind::InvalidCharacter(c) => write!(f, "invalid character {c:?}"),
+            ParseErrorKind::IncompleteRow(cells) => write!(f, "the line ends in the middle of a row after {cells} cell(s)"),
+            ParseErrorKind::UnknownSize(cells) => write!(f, "{cells} cell(s) are neither a row nor a whole puzzle of a known size"),
+            ParseErrorKind::IncompletePuzzle(cells) => write!(f, "the puzzle ends after {cells} cell(s)"),
+            ParseErrorKind::TooManyCells => write!(f, "more cells than a puzzle holds"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A region map can't give the regions of a Jigsaw board, see `Layout::with_regions`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegionError {
+    /// The text isn't a map of a known size, or has a character that doesn't stand for a region
+    Parse(ParseError),
+    /// The map has this many rows or columns, which isn't the size of the board
+    WrongSize(usize),
+    /// The region of the cell is above the number of regions the board has
+    UnknownRegion(Point<usize>),
+    /// The region, counted from 0, has this many cells instead of one per row
+    RegionSize {
+        /// Region that is too small or too big
+        region: usize,
+        /// Number of its cells
+        cells: usize,
+    },
+}
+
+impl From<ParseError> for RegionError {
+    fn from(error: ParseError) -> Self {
+        RegionError::Parse(error)
+    }
+}
+
+impl std::fmt::Display for RegionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegionError::Parse(e) => e.fmt(f),
+            RegionError::WrongSize(size) => write!(f, "the map has {size} rows or columns, not the size of the board"),
+            RegionError::UnknownRegion(cell) => write!(f, "the region of {cell} doesn't exist on this board"),
+            RegionError::RegionSize { region, cells } => write!(f, "region {} has {cells} cell(s) instead of one per row", region + 1),
+        }
+    }
+}
+
+impl std::error::Error for RegionError {}
+
+/// The board can't be used as a puzzle
+#[derive(Debug, Clone, PartialEq)]
+pub enum PuzzleError {
+    /// The clues can't be completed into a valid grid
+    NoSolution(ErrorNoSolution),
+    /// The clues fit more than one grid, so there is no single answer to find
+    MultipleSolutions,
+}
+
+impl std::fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PuzzleError::NoSolution(e) => e.fmt(f),
+            PuzzleError::MultipleSolutions => write!(f, "Sudoku has more than one solution"),
+        }
+    }
+}
+
+impl std::error::Error for PuzzleError {}
+
+/// A search was stopped before it could finish, see `Budget`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interrupted {
+    /// The iterations or the time of the budget ran out
+    Timeout,
+    /// The budget's `CancelToken` was cancelled
+    Cancelled,
+}
+
+impl std::fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Interrupted::Timeout => write!(f, "Gave up before finding a solution, the search took too long"),
+            Interrupted::Cancelled => write!(f, "The search was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+/// Solving with a `Budget` failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveError {
+    /// The sudoku can't be solved
+    NoSolution(ErrorNoSolution),
+    /// The search stopped early, so it is not known whether there is a solution
+    Interrupted(Interrupted),
+}
+
+impl From<Interrupted> for SolveError {
+    fn from(interrupted: Interrupted) -> Self {
+        SolveError::Interrupted(interrupted)
+    }
+}
+
+impl std::fmt::Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::NoSolution(e) => e.fmt(f),
+            SolveError::Interrupted(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// Generating a puzzle failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenerateError {
+    /// No grid fits the layout, or filling one failed too many times in a row, see `PuzzleGenerator::generate`
+    NoGrid,
+    /// The budget ran out or was cancelled before the puzzle was finished
+    Interrupted(Interrupted),
+}
+
+impl From<Interrupted> for GenerateError {
+    fn from(interrupted: Interrupted) -> Self {
+        GenerateError::Interrupted(interrupted)
+    }
+}
+
+impl std::fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GenerateError::NoGrid => write!(f, "Couldn't fill a grid that fits the regions"),
+            GenerateError::Interrupted(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for GenerateError {}
+
+/// Rating a sudoku failed, see `Rating`
+#[derive(Debug, Clone, PartialEq)]
+pub enum RateError {
+    /// The sudoku can't be solved
+    NoSolution(ErrorNoSolution),
+    /// The layout has Killer Sudoku cages, which none of the techniques makes use of,
+    /// so every Killer puzzle would get the score of a puzzle they can't finish
+    Unrated,
+}
+
+impl From<ErrorNoSolution> for RateError {
+    fn from(error: ErrorNoSolution) -> Self {
+        RateError::NoSolution(error)
+    }
+}
+
+impl std::fmt::Display for RateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RateError::NoSolution(e) => e.fmt(f),
+            RateError::Unrated => write!(f, "Killer Sudoku isn't rated, the techniques don't make use of cages"),
+        }
+    }
+}
+
+impl std::error::Error for RateError {}
@@ -0,0 +1,371 @@
+use std::num::NonZeroU8;
+use std::sync::Arc;
+
+use rand::Rng;
+
+use super::budget::{Budget, Meter};
+use super::cell::{value_to_mask, Cell};
+use super::layout::{Layout, Shape};
+use super::point::Point;
+use super::solver_event::{SolverEvent, SolverObserver};
+use super::sudoku::SudokuBoard;
+use super::sudoku_errors::*;
+
+/// Everything the solver knows about the board
+#[derive(Debug, Clone)]
+struct SolverState {
+    /// Cells indexed by `y * size + x`
+    board: Vec<Cell>,
+    // Bit `n - 1` is set if the value `n` is already placed in the unit,
+    // one mask per unit in the order of `Layout::units`
+    unit_masks: Vec<u32>,
+}
+
+/// A state saved before a guess, with the guessed value already ruled out of its cell
+#[derive(Debug, Clone)]
+struct SavedState {
+    state: SolverState,
+    cell: Point<usize>,
+    value: u8,
+}
+
+/// Backtracking solver: it fills the cell with the fewest possible values, propagates the choice to its peers
+/// and the other constraints of the layout, and restores a saved state when it hits a contradiction.
+#[derive(Debug, Clone)]
+pub struct SudokuSolver {
+    layout: Arc<Layout>,
+    state: SolverState,
+    previous_states: Vec<SavedState>,
+}
+
+impl SudokuSolver {
+    /// Creates a solver for a classic 9x9 board without any values
+    pub fn new_empty() -> Self {
+        Self::empty(Layout::shared(Shape::CLASSIC))
+    }
+
+    /// Creates a solver for a board of the layout without any values
+    pub fn empty(layout: Arc<Layout>) -> Self {
+        SudokuSolver {
+            state: SolverState {
+                board: vec![Cell::new_empty(layout.size()); layout.cell_count()],
+                unit_masks: vec![0; layout.units().len()],
+            },
+            // There is at most one guess per cell
+            previous_states: Vec::with_capacity(layout.cell_count()),
+            layout,
+        }
+    }
+
+    /// Creates a solver for the given board, its shape is worked out from the number of rows. <br>
+    /// Fails if the values already break a rule or leave a cell without possible values.
+    /// Panics if no shape has that many rows, see `Shape::for_size`.
+    pub fn new(starting_state: &SudokuBoard) -> Result<Self, ErrorNoSolution> {
+        Self::with_layout(Layout::for_size(starting_state.len()), starting_state)
+    }
+
+    /// Same as `new`, for a board of the given layout
+    pub fn with_layout(layout: Arc<Layout>, starting_state: &SudokuBoard) -> Result<Self, ErrorNoSolution> {
+        let mut sudoku = SudokuSolver::empty(layout);
+
+        for (y, row) in starting_state.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if let Some(cell) = cell {
+                    sudoku.place(Point::new(x, y), u8::from(*cell), &mut ignore).map_err(|_| ErrorNoSolution::default())?;
+                }
+            }
+        }
+        // Constraints like sums rule out values even on an empty board
+        let pruned: Vec<usize> = (0..sudoku.layout.constraints().len())
+            .filter(|&index| sudoku.layout.constraints()[index].prunes_beyond_peers())
+            .collect();
+        sudoku.prune(pruned, &mut ignore).map_err(|_| ErrorNoSolution::default())?;
+
+        Ok(sudoku)
+    }
+
+    /// Constraints, units and peers of the board being solved
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    /// Current state of every cell, with the possible values of the empty ones, indexed as `cells[y * size + x]`
+    pub fn cells(&self) -> &[Cell] {
+        &self.state.board
+    }
+
+    fn index(&self, cell_coords: &Point<usize>) -> usize {
+        cell_coords.y * self.layout.size() + cell_coords.x
+    }
+
+    fn get_cell_mut(&mut self, cell_coords: &Point<usize>) -> &mut Cell {
+        let index = self.index(cell_coords);
+        &mut self.state.board[index]
+    }
+
+    fn board_to_option_array(&self) -> SudokuBoard {
+        self.state.board.chunks(self.layout.size()).map(|row| row.iter().map(
+            |cell| {
+                match cell {
+                    Cell::Collapsed(n) => Some(NonZeroU8::try_from(*n).unwrap()),
+                    Cell::Uncollapsed(_) => None
+                }
+            }).collect()).collect()
+    }
+
+    /// Returns the first solution found, trying the smallest possible value at every guess
+    pub fn solve(self) -> Result<SudokuBoard, ErrorNoSolution>{
+        self.solve_with(Self::first_possible_value)
+    }
+
+    /// Same as `solve`, but every guess picks a random possible value.
+    /// Solving an empty board this way gives a random full grid.
+    pub fn solve_random<R: Rng + ?Sized>(self, rng: &mut R) -> Result<SudokuBoard, ErrorNoSolution>{
+        self.solve_with(|possible_values| Self::random_possible_value(rng, possible_values))
+    }
+
+    /// Same as `solve_random`, but gives up once the budget runs out. <br>
+    /// Random guesses sometimes lead the search astray for a long time, e.g. on Jigsaw boards,
+    /// starting again from scratch is usually faster than waiting.
+    pub fn solve_random_within<R: Rng + ?Sized>(&mut self, rng: &mut R, budget: &Budget) -> Result<SudokuBoard, SolveError> {
+        let mut choose_value = |possible_values| Self::random_possible_value(rng, possible_values);
+        if self.search(&mut choose_value, &mut budget.start(), &mut ignore)? {
+            Ok(self.board_to_option_array())
+        } else {
+            Err(SolveError::NoSolution(ErrorNoSolution::default()))
+        }
+    }
+
+    /// Same as `solve`, but gives up once the budget runs out. <br>
+    /// After an `Interrupted` error the solver keeps its progress, so calling it again with a new budget
+    /// picks the search up where it stopped. A frontend can solve in slices this way, doing other work in between.
+    /// Calling it again after it returned a solution or `NoSolution` is pointless.
+    pub fn solve_within(&mut self, budget: &Budget) -> Result<SudokuBoard, SolveError> {
+        self.solve_observed(budget, &mut ignore)
+    }
+
+    /// Same as `solve_within`, but reports every step of the search to the observer,
+    /// e.g. to animate how the solver works. A budget of one iteration gives a single guess or backtrack at a time.
+    pub fn solve_observed(&mut self, budget: &Budget, observer: &mut impl SolverObserver) -> Result<SudokuBoard, SolveError> {
+        if self.search(&mut Self::first_possible_value, &mut budget.start(), observer)? {
+            Ok(self.board_to_option_array())
+        } else {
+            Err(SolveError::NoSolution(ErrorNoSolution::default()))
+        }
+    }
+
+    /// Same as `solve_within`, but spends what is left of a budget several searches share
+    pub(crate) fn solve_metered(&mut self, meter: &mut Meter) -> Result<SudokuBoard, SolveError> {
+        if self.search(&mut Self::first_possible_value, meter, &mut ignore)? {
+            Ok(self.board_to_option_array())
+        } else {
+            Err(SolveError::NoSolution(ErrorNoSolution::default()))
+        }
+    }
+
+    fn solve_with(mut self, mut choose_value: impl FnMut(u32) -> u8) -> Result<SudokuBoard, ErrorNoSolution>{
+        // An unlimited budget is never interrupted
+        match self.search(&mut choose_value, &mut Budget::unlimited().start(), &mut ignore) {
+            Ok(true) => Ok(self.board_to_option_array()),
+            _ => Err(ErrorNoSolution::default())
+        }
+    }
+
+    /// Runs the search until it finds a solution (true), runs out of guesses (false) or runs out of budget
+    fn search(&mut self, choose_value: &mut impl FnMut(u32) -> u8, meter: &mut Meter, observer: &mut impl SolverObserver) -> Result<bool, Interrupted> {
+        loop {
+            meter.tick()?;
+            match self.solve_iteration(choose_value, observer) {
+                Ok(true) => return Ok(true),
+                Ok(false) => continue,
+                Err(_) => if !self.backtrack(observer) { return Ok(false) }
+            }
+        }
+    }
+
+    /// Counts the solutions of the sudoku, stopping as soon as `limit` of them were found. <br>
+    /// Use a limit of 2 to check whether the solution is unique.
+    pub fn count_solutions(self, limit: usize) -> usize {
+        // An unlimited budget is never interrupted
+        self.count_solutions_within(limit, &Budget::unlimited()).unwrap_or(0)
+    }
+
+    /// Same as `count_solutions`, but gives up once the budget runs out
+    pub fn count_solutions_within(self, limit: usize, budget: &Budget) -> Result<usize, Interrupted> {
+        self.count_solutions_metered(limit, &mut budget.start())
+    }
+
+    /// Same as `count_solutions_within`, but spends what is left of a budget several searches share
+    pub(crate) fn count_solutions_metered(mut self, limit: usize, meter: &mut Meter) -> Result<usize, Interrupted> {
+        let mut count = 0;
+
+        while count < limit {
+            meter.tick()?;
+            match self.solve_iteration(&mut Self::first_possible_value, &mut ignore) {
+                Ok(true) => {
+                    count += 1;
+                    // Keep backtracking past the found solution to look for the next one
+                    if !self.backtrack(&mut ignore) { break }
+                },
+                Ok(false) => continue,
+                Err(_) => if !self.backtrack(&mut ignore) { break }
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Restores the most recent saved state. Returns false if there is nothing left to try.
+    fn backtrack(&mut self, observer: &mut impl SolverObserver) -> bool {
+        match self.previous_states.pop() {
+            Some(SavedState { state, cell, value }) => {
+                self.state = state;
+                observer.on_event(&SolverEvent::Backtrack { cell, value, depth: self.previous_states.len() });
+                true
+            },
+            None => false
+        }
+    }
+
+    fn first_possible_value(possible_values: u32) -> u8 {
+        possible_values.trailing_zeros() as u8 + 1
+    }
+
+    fn random_possible_value<R: Rng + ?Sized>(rng: &mut R, possible_values: u32) -> u8 {
+        let skip = rng.random_range(0..possible_values.count_ones());
+        let mut remaining = possible_values;
+        for _ in 0..skip {
+            remaining &= remaining - 1;
+        }
+        Self::first_possible_value(remaining)
+    }
+
+    // returns true if sudoku is solved, false if not and Err if there is a contradiction
+    fn solve_iteration(&mut self, choose_value: &mut impl FnMut(u32) -> u8, observer: &mut impl SolverObserver) -> Result<bool, ()> {
+        match self.get_coords_of_uncollapsed_cell_with_lowest_entropy() {
+            Some(cell_coords) => {
+                let value = choose_value(self.get_cell_mut(&cell_coords).get_possible_values());
+                self.collapse_cell_and_save_state(cell_coords, value, observer)?;
+                Ok(false)
+            },
+            None => Ok(true) // sudoku is solved
+        }
+    }
+
+    fn collapse_cell_and_save_state(&mut self, cell_coords: Point<usize>, value: u8, observer: &mut impl SolverObserver) -> Result<(), ()> {
+        let cell = self.get_cell_mut(&cell_coords);
+        let should_save = cell.get_entropy() > 1;
+        let value_with_collapsed_num_removed = cell.collapse(value);
+
+        if should_save {
+            let mut state = self.state.clone();
+            state.board[self.index(&cell_coords)] = value_with_collapsed_num_removed;
+            self.previous_states.push(SavedState { state, cell: cell_coords, value });
+        }
+        observer.on_event(&SolverEvent::Collapse { cell: cell_coords, value, guess: should_save });
+
+        self.place(cell_coords, value, observer)
+    }
+
+    /// Writes the value into the cell and removes it from the possible values of its peers
+    fn place(&mut self, cell_coords: Point<usize>, value: u8, observer: &mut impl SolverObserver) -> Result<(), ()> {
+        let mask = value_to_mask(value);
+        let index = self.index(&cell_coords);
+        let layout = Arc::clone(&self.layout);
+        let units = layout.units_of(index);
+        let state = &mut self.state;
+        if units.iter().any(|&unit| state.unit_masks[unit] & mask != 0) {
+            // The value is already used somewhere in a row, column, region or other unit of the cell
+            observer.on_event(&SolverEvent::Contradiction { cell: cell_coords });
+            return Err(());
+        }
+        for &unit in units {
+            state.unit_masks[unit] |= mask;
+        }
+        state.board[index] = Cell::new_filled(value);
+
+        self.propagate_collapse(cell_coords, value, observer)?;
+        self.prune(layout.pruned_by(index).to_vec(), observer)
+    }
+
+    /// Lets the constraints rule out values, starting with the ones at the given indices into `Layout::constraints`.
+    /// A constraint that narrows down a cell gets the other constraints holding that cell to prune again.
+    fn prune(&mut self, mut pending: Vec<usize>, observer: &mut impl SolverObserver) -> Result<(), ()> {
+        let layout = Arc::clone(&self.layout);
+        let mut candidates = Vec::new();
+        while let Some(constraint_index) = pending.pop() {
+            let constraint = &layout.constraints()[constraint_index];
+            candidates.clear();
+            candidates.extend(constraint.cells().iter().map(|&cell| self.state.board[cell].get_possible_values()));
+            constraint.prune(&mut candidates);
+
+            for (&cell, &mask) in constraint.cells().iter().zip(&candidates) {
+                let board_cell = &mut self.state.board[cell];
+                let removed = board_cell.get_possible_values() & !mask;
+                if removed == 0 {
+                    continue;
+                }
+                let point = Point::new(layout.column_of(cell), layout.row_of(cell));
+                if let Cell::Uncollapsed(_) = board_cell {
+                    for value in (1..=layout.size() as u8).filter(|&value| removed & value_to_mask(value) != 0) {
+                        observer.on_event(&SolverEvent::RemoveCandidate { cell: point, value });
+                    }
+                }
+                if board_cell.restrict(mask).is_err() {
+                    observer.on_event(&SolverEvent::Contradiction { cell: point });
+                    return Err(());
+                }
+                for &other in layout.pruned_by(cell) {
+                    if other != constraint_index && !pending.contains(&other) {
+                        pending.push(other);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn propagate_collapse(&mut self, cell_coords: Point<usize>, value: u8, observer: &mut impl SolverObserver) -> Result<(), ()> {
+        let mask = value_to_mask(value);
+        let layout = Arc::clone(&self.layout);
+        for &relative in layout.peers(self.index(&cell_coords)) {
+            let peer = Point::new(layout.column_of(relative), layout.row_of(relative));
+            let cell = &mut self.state.board[relative];
+            let was_possible = matches!(cell, Cell::Uncollapsed(_)) && cell.get_possible_values() & mask != 0;
+            let removed = cell.remove(value);
+            if was_possible {
+                observer.on_event(&SolverEvent::RemoveCandidate { cell: peer, value });
+            }
+            if removed.is_err() {
+                observer.on_event(&SolverEvent::Contradiction { cell: peer });
+                return Err(());
+            }
+        }
+        Ok(())
+    }
+
+    fn get_coords_of_uncollapsed_cell_with_lowest_entropy(&self) -> Option<Point<usize>> {
+        let mut cell = None::<Point<usize>>;
+        let mut lowest_entropy = u8::MAX;
+
+        for (index, current_cell) in self.state.board.iter().enumerate() {
+            if let Cell::Collapsed(_) = current_cell {
+                continue;
+            }
+            let current_entropy = current_cell.get_entropy();
+            if current_entropy < lowest_entropy {
+                lowest_entropy = current_entropy;
+                cell = Some(Point::new(self.layout.column_of(index), self.layout.row_of(index)));
+                if lowest_entropy == 1 {
+                    // Can't do any better than a cell with a single possible value
+                    return cell;
+                }
+            }
+        }
+
+        cell
+    }
+}
+
+/// Observer for searches nobody watches
+fn ignore(_: &SolverEvent) {}
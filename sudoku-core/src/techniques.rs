@@ -0,0 +1,577 @@
+//! Searches for the techniques used by the logical solver.
+//! Every search returns the first deduction found that makes progress, or `None`.
+
+use super::cell::value_to_mask;
+use super::layout::MAX_SIZE;
+use super::logical_solver::{Deduction, Grid, Technique};
+
+fn values_of(mask: u32) -> impl Iterator<Item = u8> {
+    (1..=MAX_SIZE as u8).filter(move |&value| mask & value_to_mask(value) != 0)
+}
+
+/// All the ways to pick `size` items from `items`, keeping their order
+fn combinations<T: Copy>(items: &[T], size: usize) -> Vec<Vec<T>> {
+    if size == 0 {
+        return vec![Vec::new()];
+    }
+    let mut result = Vec::new();
+    for (i, &first) in items.iter().enumerate() {
+        for mut rest in combinations(&items[i + 1..], size - 1) {
+            rest.insert(0, first);
+            result.push(rest);
+        }
+    }
+    result
+}
+
+fn empty_cells(grid: &Grid) -> impl Iterator<Item = usize> + '_ {
+    (0..grid.layout.cell_count()).filter(|&cell| grid.values[cell] == 0)
+}
+
+/// The only cell of a unit that can hold a value
+pub fn hidden_single(grid: &Grid) -> Option<Deduction> {
+    // Regions first, they are the easiest to spot, the diagonals and other added units last
+    let layout = &grid.layout;
+    for unit in layout.regions().iter().chain(layout.rows()).chain(layout.columns()).chain(layout.added_units()) {
+        for value in grid.value_range() {
+            if let [cell] = grid.cells_with_candidate(unit, value)[..] {
+                let pattern = unit.iter().copied().filter(|&c| c != cell).collect();
+                return Some(Deduction::placement(Technique::HiddenSingle, cell, value, pattern));
+            }
+        }
+    }
+    None
+}
+
+/// A cell with only one possible value
+pub fn naked_single(grid: &Grid) -> Option<Deduction> {
+    for cell in empty_cells(grid) {
+        if grid.candidates[cell].count_ones() == 1 {
+            let value = grid.candidates[cell].trailing_zeros() as u8 + 1;
+            let pattern = grid.layout.peers(cell).iter().copied().filter(|&peer| grid.values[peer] != 0).collect();
+            return Some(Deduction::placement(Technique::NakedSingle, cell, value, pattern));
+        }
+    }
+    None
+}
+
+/// All candidates of a value inside a region are on one line,
+/// so the value can be removed from the rest of that line
+pub fn pointing(grid: &Grid) -> Option<Deduction> {
+    let layout = &grid.layout;
+    for (region, unit) in layout.regions().iter().enumerate() {
+        for value in grid.value_range() {
+            let cells = grid.cells_with_candidate(unit, value);
+            if cells.len() < 2 {
+                continue;
+            }
+            for line in [&layout.rows()[layout.row_of(cells[0])], &layout.columns()[layout.column_of(cells[0])]] {
+                if !cells.iter().all(|cell| line.contains(cell)) {
+                    continue;
+                }
+                let eliminations = grid.cells_with_candidate(line, value).into_iter()
+                    .filter(|&cell| layout.region_of(cell) != region)
+                    .map(|cell| (cell, value))
+                    .collect();
+                if let Some(deduction) = Deduction::eliminations(Technique::Pointing, eliminations, cells.clone()) {
+                    return Some(deduction);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// All candidates of a value on a line are inside one region,
+/// so the value can be removed from the rest of that region
+pub fn claiming(grid: &Grid) -> Option<Deduction> {
+    let layout = &grid.layout;
+    for line in layout.rows().iter().chain(layout.columns()) {
+        for value in grid.value_range() {
+            let cells = grid.cells_with_candidate(line, value);
+            if cells.len() < 2 {
+                continue;
+            }
+            let region = layout.region_of(cells[0]);
+            if !cells.iter().all(|&cell| layout.region_of(cell) == region) {
+                continue;
+            }
+            let eliminations = grid.cells_with_candidate(&layout.regions()[region], value).into_iter()
+                .filter(|cell| !line.contains(cell))
+                .map(|cell| (cell, value))
+                .collect();
+            if let Some(deduction) = Deduction::eliminations(Technique::Claiming, eliminations, cells) {
+                return Some(deduction);
+            }
+        }
+    }
+    None
+}
+
+/// `size` cells of a unit that together can only hold `size` values,
+/// so no other cell of the unit can hold them
+pub fn naked_subset(grid: &Grid, size: usize, technique: Technique) -> Option<Deduction> {
+    for unit in grid.layout.units() {
+        let pool: Vec<usize> = unit.iter().copied()
+            .filter(|&cell| grid.values[cell] == 0 && (2..=size as u32).contains(&grid.candidates[cell].count_ones()))
+            .collect();
+        for subset in combinations(&pool, size) {
+            let values = subset.iter().fold(0, |mask, &cell| mask | grid.candidates[cell]);
+            if values.count_ones() as usize != size {
+                continue;
+            }
+            let eliminations = unit.iter().copied()
+                .filter(|cell| !subset.contains(cell))
+                .flat_map(|cell| values_of(grid.candidates[cell] & values).map(move |value| (cell, value)))
+                .collect();
+            if let Some(deduction) = Deduction::eliminations(technique, eliminations, subset) {
+                return Some(deduction);
+            }
+        }
+    }
+    None
+}
+
+/// `size` values that can only go into the same `size` cells of a unit,
+/// so those cells can't hold any other value
+pub fn hidden_subset(grid: &Grid, size: usize, technique: Technique) -> Option<Deduction> {
+    for unit in grid.layout.units() {
+        let pool: Vec<u8> = grid.value_range()
+            .filter(|&value| (2..=size).contains(&grid.cells_with_candidate(unit, value).len()))
+            .collect();
+        for subset in combinations(&pool, size) {
+            let mut cells: Vec<usize> = subset.iter().flat_map(|&value| grid.cells_with_candidate(unit, value)).collect();
+            cells.sort_unstable();
+            cells.dedup();
+            if cells.len() != size {
+                continue;
+            }
+            let values = subset.iter().fold(0, |mask, &value| mask | value_to_mask(value));
+            let eliminations = cells.iter()
+                .flat_map(|&cell| values_of(grid.candidates[cell] & !values).map(move |value| (cell, value)))
+                .collect();
+            if let Some(deduction) = Deduction::eliminations(technique, eliminations, cells) {
+                return Some(deduction);
+            }
+        }
+    }
+    None
+}
+
+/// X-Wing (size 2), Swordfish (size 3) and Jellyfish (size 4). <br>
+/// A value confined to the same `size` columns on `size` rows (or the other way around)
+/// can be removed from the rest of those columns.
+pub fn fish(grid: &Grid, size: usize, technique: Technique) -> Option<Deduction> {
+    let layout = &grid.layout;
+    for value in grid.value_range() {
+        for (base, cover) in [(layout.rows(), layout.columns()), (layout.columns(), layout.rows())] {
+            // Lines with their candidate positions as a mask over the cross lines
+            let lines: Vec<(usize, u32)> = (0..layout.size())
+                .map(|line| {
+                    let positions = base[line].iter().enumerate()
+                        .filter(|&(_, &cell)| grid.has_candidate(cell, value))
+                        .fold(0u32, |mask, (position, _)| mask | 1 << position);
+                    (line, positions)
+                })
+                .filter(|&(_, positions)| (2..=size as u32).contains(&positions.count_ones()))
+                .collect();
+
+            for subset in combinations(&lines, size) {
+                let positions = subset.iter().fold(0u32, |mask, &(_, p)| mask | p);
+                if positions.count_ones() as usize != size {
+                    continue;
+                }
+                let base_lines: Vec<usize> = subset.iter().map(|&(line, _)| line).collect();
+                let eliminations = (0..layout.size())
+                    .filter(|position| positions & 1 << position != 0)
+                    .flat_map(|position| cover[position].iter().enumerate())
+                    .filter(|&(line, &cell)| !base_lines.contains(&line) && grid.has_candidate(cell, value))
+                    .map(|(_, &cell)| (cell, value))
+                    .collect();
+                let pattern = base_lines.iter()
+                    .flat_map(|&line| grid.cells_with_candidate(&base[line], value))
+                    .collect();
+                if let Some(deduction) = Deduction::eliminations(technique, eliminations, pattern) {
+                    return Some(deduction);
+                }
+            }
+        }
+    }
+    None
+}
+
+fn bivalue_cells(grid: &Grid) -> Vec<usize> {
+    empty_cells(grid).filter(|&cell| grid.candidates[cell].count_ones() == 2).collect()
+}
+
+/// Removes the value from every cell that sees all the given cells
+fn eliminate_seen_by_all(grid: &Grid, cells: &[usize], value: u8) -> Vec<(usize, u8)> {
+    empty_cells(grid)
+        .filter(|&cell| !cells.contains(&cell) && grid.has_candidate(cell, value))
+        .filter(|&cell| cells.iter().all(|&other| grid.layout.sees(cell, other)))
+        .map(|cell| (cell, value))
+        .collect()
+}
+
+/// A pivot XY sees two pincers XZ and YZ. Whichever value the pivot takes,
+/// one of the pincers is Z, so Z can be removed from cells seeing both pincers.
+pub fn xy_wing(grid: &Grid) -> Option<Deduction> {
+    let bivalue = bivalue_cells(grid);
+    for &pivot in &bivalue {
+        let pivot_values = grid.candidates[pivot];
+        for &first in bivalue.iter().filter(|&&c| grid.layout.sees(pivot, c)) {
+            let shared = grid.candidates[first] & pivot_values;
+            if shared.count_ones() != 1 {
+                continue;
+            }
+            let z = grid.candidates[first] & !pivot_values;
+            for &second in bivalue.iter().filter(|&&c| c != first && grid.layout.sees(pivot, c)) {
+                if grid.candidates[second] != (pivot_values & !shared) | z {
+                    continue;
+                }
+                let z_value = z.trailing_zeros() as u8 + 1;
+                let eliminations = eliminate_seen_by_all(grid, &[first, second], z_value);
+                if let Some(deduction) = Deduction::eliminations(Technique::XYWing, eliminations, vec![pivot, first, second]) {
+                    return Some(deduction);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Like the XY-Wing, but the pivot can also be Z itself,
+/// so only cells seeing the pivot and both pincers lose Z
+pub fn xyz_wing(grid: &Grid) -> Option<Deduction> {
+    let bivalue = bivalue_cells(grid);
+    for pivot in empty_cells(grid).filter(|&cell| grid.candidates[cell].count_ones() == 3) {
+        let pivot_values = grid.candidates[pivot];
+        let pincers: Vec<usize> = bivalue.iter().copied()
+            .filter(|&cell| grid.layout.sees(pivot, cell) && grid.candidates[cell] & !pivot_values == 0)
+            .collect();
+        for pair in combinations(&pincers, 2) {
+            let (first, second) = (pair[0], pair[1]);
+            let z = grid.candidates[first] & grid.candidates[second];
+            if z.count_ones() != 1 || grid.candidates[first] == grid.candidates[second] {
+                continue;
+            }
+            let z_value = z.trailing_zeros() as u8 + 1;
+            let eliminations = eliminate_seen_by_all(grid, &[pivot, first, second], z_value);
+            if let Some(deduction) = Deduction::eliminations(Technique::XYZWing, eliminations, vec![pivot, first, second]) {
+                return Some(deduction);
+            }
+        }
+    }
+    None
+}
+
+/// Chains of cells where a value has exactly two places in a unit are coloured alternately.
+/// One colour is true, so a colour appearing twice in a unit is false,
+/// and a cell seeing both colours can't hold the value.
+pub fn simple_colouring(grid: &Grid) -> Option<Deduction> {
+    let layout = &grid.layout;
+    for value in grid.value_range() {
+        let mut links: Vec<Vec<usize>> = vec![Vec::new(); layout.cell_count()];
+        for unit in layout.units() {
+            if let [a, b] = grid.cells_with_candidate(unit, value)[..] {
+                links[a].push(b);
+                links[b].push(a);
+            }
+        }
+
+        let mut colours = vec![0u8; layout.cell_count()];
+        for start in 0..layout.cell_count() {
+            if links[start].is_empty() || colours[start] != 0 {
+                continue;
+            }
+            // Colour the whole chain with 1 and 2
+            let mut chain = vec![start];
+            colours[start] = 1;
+            let mut i = 0;
+            while i < chain.len() {
+                let cell = chain[i];
+                for &next in &links[cell] {
+                    if colours[next] == 0 {
+                        colours[next] = 3 - colours[cell];
+                        chain.push(next);
+                    }
+                }
+                i += 1;
+            }
+            if chain.len() < 3 {
+                continue;
+            }
+
+            for colour in [1, 2] {
+                let same: Vec<usize> = chain.iter().copied().filter(|&c| colours[c] == colour).collect();
+                let wrapped = combinations(&same, 2).iter().any(|pair| layout.sees(pair[0], pair[1]));
+                if wrapped {
+                    let eliminations = same.iter().map(|&cell| (cell, value)).collect();
+                    return Deduction::eliminations(Technique::SimpleColouring, eliminations, chain);
+                }
+            }
+
+            let eliminations: Vec<(usize, u8)> = empty_cells(grid)
+                .filter(|&cell| !chain.contains(&cell) && grid.has_candidate(cell, value))
+                .filter(|&cell| {
+                    let sees_colour = |colour| chain.iter().any(|&c| colours[c] == colour && layout.sees(cell, c));
+                    sees_colour(1) && sees_colour(2)
+                })
+                .map(|cell| (cell, value))
+                .collect();
+            if let Some(deduction) = Deduction::eliminations(Technique::SimpleColouring, eliminations, chain) {
+                return Some(deduction);
+            }
+        }
+    }
+    None
+}
+
+/// Four cells on two rows, two columns and two regions can't all be limited to the same
+/// two values, as the values could be swapped and the puzzle wouldn't have a unique solution. <br>
+/// Type 1: three corners hold only the pair, so the fourth can't be either of the two values. <br>
+/// Type 2: two corners on a line hold the pair plus the same extra value, which must then be
+/// in one of them and can be removed from cells seeing both. <br>
+/// Not used on layouts with added constraints, which might allow only one of the two fillings,
+/// nor with irregular regions, where the corners might not pair up inside two regions.
+pub fn unique_rectangle(grid: &Grid) -> Option<Deduction> {
+    let layout = &grid.layout;
+    if layout.has_added_constraints() || layout.has_irregular_regions() {
+        return None;
+    }
+    let lines: Vec<usize> = (0..layout.size()).collect();
+    for rows in combinations(&lines, 2) {
+        for columns in combinations(&lines, 2) {
+            let corners = [
+                rows[0] * layout.size() + columns[0],
+                rows[0] * layout.size() + columns[1],
+                rows[1] * layout.size() + columns[0],
+                rows[1] * layout.size() + columns[1],
+            ];
+            if corners.iter().any(|&cell| grid.values[cell] != 0) {
+                continue;
+            }
+            let spans_two_regions = layout.region_of(corners[0]) == layout.region_of(corners[1])
+                || layout.region_of(corners[0]) == layout.region_of(corners[2]);
+            if !spans_two_regions {
+                continue;
+            }
+            let common = corners.iter().fold(u32::MAX, |mask, &cell| mask & grid.candidates[cell]);
+            for pair in combinations(&values_of(common).collect::<Vec<u8>>(), 2) {
+                let pair_mask = value_to_mask(pair[0]) | value_to_mask(pair[1]);
+                let (floor, roof): (Vec<usize>, Vec<usize>) = corners.iter()
+                    .partition(|&&cell| grid.candidates[cell] == pair_mask);
+
+                if let [target] = roof[..] {
+                    let eliminations = pair.iter().map(|&value| (target, value)).collect();
+                    return Deduction::eliminations(Technique::UniqueRectangle, eliminations, corners.to_vec());
+                }
+
+                let roof_on_one_line = roof.len() == 2
+                    && (layout.row_of(roof[0]) == layout.row_of(roof[1]) || layout.column_of(roof[0]) == layout.column_of(roof[1]));
+                if floor.len() == 2 && roof_on_one_line {
+                    let extra = grid.candidates[roof[0]] & !pair_mask;
+                    if extra.count_ones() != 1 || grid.candidates[roof[1]] & !pair_mask != extra {
+                        continue;
+                    }
+                    let eliminations = eliminate_seen_by_all(grid, &roof, extra.trailing_zeros() as u8 + 1);
+                    if let Some(deduction) = Deduction::eliminations(Technique::UniqueRectangle, eliminations, corners.to_vec()) {
+                        return Some(deduction);
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{Layout, Shape, Sudoku};
+
+    /// A generated puzzle that shows most of the techniques before any value is placed
+    const PUZZLE: &str = "...86..3.8..9....2....71.9..2.....761.....2..5....8...3..7.45.............72..94.";
+    /// The same puzzle a few values further into the logical solve
+    const PUZZLE_LATER: &str = "7..862.3.8..9....2....71.9..2.....761....72..57..28...3..7.45.............72..94.";
+    /// A puzzle close to the end of its logical solve
+    const NEARLY_SOLVED: &str = "52419.736.614..589.985.6.4.659.42..343..1965.1..365.94..36.49.58.69534..9452.136.";
+
+    /// The grid the logical solver starts from: the values of the puzzle and the candidates their peers leave
+    fn grid(puzzle: &str) -> Grid {
+        let layout = Layout::shared(Shape::CLASSIC);
+        let mut grid = Grid {
+            values: vec![0; layout.cell_count()],
+            candidates: vec![layout.all_values(); layout.cell_count()],
+            layout: Arc::clone(&layout),
+        };
+        for (cell, c) in puzzle.chars().enumerate() {
+            let Some(value) = c.to_digit(10).map(|value| value as u8).filter(|&value| value != 0) else { continue };
+            grid.values[cell] = value;
+            grid.candidates[cell] = 0;
+            for &peer in layout.peers(cell) {
+                grid.candidates[peer] &= !value_to_mask(value);
+            }
+        }
+        grid
+    }
+
+    /// Checks that the technique found a deduction that agrees with the solution of the puzzle
+    fn find(puzzle: &str, technique: Technique, search: impl Fn(&Grid) -> Option<Deduction>) -> Deduction {
+        let deduction = search(&grid(puzzle)).expect("the technique applies to the puzzle");
+        assert_eq!(deduction.technique, technique);
+
+        let mut solved: Sudoku = puzzle.parse().unwrap();
+        solved.solve().unwrap();
+        let solution = |cell: usize| solved.get_cell(cell % 9, cell / 9).unwrap().get();
+        if let Some((cell, value)) = deduction.placement {
+            assert_eq!(value, solution(cell));
+        }
+        for &(cell, value) in &deduction.eliminations {
+            assert_ne!(value, solution(cell), "removed the value of the solution from cell {cell}");
+        }
+        deduction
+    }
+
+    #[test]
+    fn hidden_single() {
+        let deduction = find(PUZZLE, Technique::HiddenSingle, super::hidden_single);
+        assert_eq!(deduction.placement, Some((5, 2)));
+    }
+
+    #[test]
+    fn hidden_single_on_a_diagonal() {
+        // Only the top left cell of the main diagonal can hold a 5, every row, column and box has room for it elsewhere
+        let layout = Arc::new(Layout::diagonal(Shape::CLASSIC));
+        let mut grid = Grid { values: vec![0; 81], candidates: vec![layout.all_values(); 81], layout };
+        for cell in (10..81).step_by(10) {
+            grid.candidates[cell] &= !value_to_mask(5);
+        }
+        let deduction = super::hidden_single(&grid).unwrap();
+        assert_eq!(deduction.placement, Some((0, 5)));
+        assert_eq!(deduction.pattern, (10..81).step_by(10).collect::<Vec<_>>());
+
+        grid.layout = Layout::shared(Shape::CLASSIC);
+        assert_eq!(super::hidden_single(&grid), None);
+    }
+
+    #[test]
+    fn naked_single() {
+        let deduction = find(PUZZLE, Technique::NakedSingle, super::naked_single);
+        assert_eq!(deduction.placement, Some((52, 1)));
+    }
+
+    #[test]
+    fn pointing() {
+        let deduction = find(PUZZLE, Technique::Pointing, super::pointing);
+        assert_eq!(deduction.eliminations, [(1, 7), (10, 7)]);
+    }
+
+    #[test]
+    fn claiming() {
+        let deduction = find(PUZZLE, Technique::Claiming, super::claiming);
+        assert_eq!(deduction.eliminations, [(0, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn naked_pair() {
+        let puzzle = "7..862.3.8..9..7.2....71.9..2.....761....72..57..28.1.3..7.45..........76.72..94.";
+        let deduction = find(puzzle, Technique::NakedPair, |grid| naked_subset(grid, 2, Technique::NakedPair));
+        assert_eq!(deduction.pattern, [14, 77]);
+        assert_eq!(deduction.eliminations, [(32, 3), (32, 5), (68, 3), (68, 5)]);
+    }
+
+    #[test]
+    fn naked_triple() {
+        let deduction = find(PUZZLE, Technique::NakedTriple, |grid| naked_subset(grid, 3, Technique::NakedTriple));
+        assert_eq!(deduction.pattern, [13, 14, 21]);
+        assert_eq!(deduction.eliminations, [(5, 5)]);
+    }
+
+    #[test]
+    fn naked_quad() {
+        let deduction = find(PUZZLE, Technique::NakedQuad, |grid| naked_subset(grid, 4, Technique::NakedQuad));
+        assert_eq!(deduction.pattern, [14, 32, 68, 77]);
+        assert_eq!(deduction.eliminations, [(5, 5), (41, 3), (41, 5), (41, 6), (41, 9)]);
+    }
+
+    #[test]
+    fn hidden_pair() {
+        let puzzle = "7..862.3.8..9..7.2....71.9..2.....761....72..57..28.1.3..7.456.........76.72..94.";
+        let deduction = find(puzzle, Technique::HiddenPair, |grid| hidden_subset(grid, 2, Technique::HiddenPair));
+        assert_eq!(deduction.pattern, [10, 11]);
+        assert_eq!(deduction.eliminations, [(10, 3), (10, 4), (10, 5), (11, 3), (11, 4), (11, 5)]);
+    }
+
+    #[test]
+    fn hidden_triple() {
+        let deduction = find(PUZZLE_LATER, Technique::HiddenTriple, |grid| hidden_subset(grid, 3, Technique::HiddenTriple));
+        assert_eq!(deduction.pattern, [18, 27, 63]);
+        assert_eq!(deduction.eliminations, [(18, 6), (63, 6)]);
+    }
+
+    #[test]
+    fn hidden_quad() {
+        let deduction = find(PUZZLE, Technique::HiddenQuad, |grid| hidden_subset(grid, 4, Technique::HiddenQuad));
+        assert_eq!(deduction.pattern, [73, 76, 77, 80]);
+        assert_eq!(deduction.eliminations, [(73, 6), (77, 6)]);
+    }
+
+    #[test]
+    fn x_wing() {
+        let deduction = find(PUZZLE_LATER, Technique::XWing, |grid| fish(grid, 2, Technique::XWing));
+        assert_eq!(deduction.pattern, [27, 63, 32, 68]);
+        assert_eq!(deduction.eliminations, [(29, 9), (31, 9), (64, 9), (65, 9), (67, 9)]);
+    }
+
+    #[test]
+    fn swordfish() {
+        let deduction = find(PUZZLE, Technique::Swordfish, |grid| fish(grid, 3, Technique::Swordfish));
+        assert_eq!(deduction.eliminations, [(0, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn jellyfish() {
+        let deduction = find(PUZZLE_LATER, Technique::Jellyfish, |grid| fish(grid, 4, Technique::Jellyfish));
+        assert_eq!(deduction.eliminations, [(64, 9), (29, 9), (65, 9), (31, 9), (67, 9)]);
+    }
+
+    #[test]
+    fn xy_wing() {
+        let puzzle = "52419.736.614..5.9.985.6.4.659.42..343..1965.1..365.94..36.49.58.69534..9452.136.";
+        let deduction = find(puzzle, Technique::XYWing, super::xy_wing);
+        assert_eq!(deduction.pattern, [24, 16, 33]);
+        assert_eq!(deduction.eliminations, [(34, 8)]);
+    }
+
+    #[test]
+    fn xyz_wing() {
+        let puzzle = "..4...7.6.614......9.5..........2.......1.65.1..3...9...36....58..95.4...4.2..3..";
+        let deduction = find(puzzle, Technique::XYZWing, super::xyz_wing);
+        assert_eq!(deduction.pattern, [24, 33, 51]);
+        assert_eq!(deduction.eliminations, [(15, 8), (60, 8)]);
+    }
+
+    #[test]
+    fn unique_rectangle() {
+        let deduction = find(NEARLY_SOLVED, Technique::UniqueRectangle, super::unique_rectangle);
+        assert_eq!(deduction.pattern, [55, 61, 64, 70]);
+        assert_eq!(deduction.eliminations, [(71, 2)]);
+    }
+
+    #[test]
+    fn unique_rectangle_is_skipped_with_added_constraints() {
+        let mut grid = grid(NEARLY_SOLVED);
+        grid.layout = Arc::new(Layout::diagonal(Shape::CLASSIC));
+        assert_eq!(super::unique_rectangle(&grid), None);
+    }
+
+    #[test]
+    fn simple_colouring() {
+        let deduction = find(PUZZLE, Technique::SimpleColouring, super::simple_colouring);
+        assert_eq!(deduction.pattern, [16, 43, 44]);
+        assert_eq!(deduction.eliminations, [(39, 5), (40, 5), (41, 5)]);
+    }
+}